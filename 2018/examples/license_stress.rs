@@ -0,0 +1,34 @@
+//! Time the license tree functions on a large random tree.
+//!
+//! Usage: `cargo run --release --example license_stress [depth] [branching]`
+
+use advent2018::license::{self, Generator};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+fn main() {
+    let mut args = std::env::args().skip(1).map(|arg| arg.parse().expect("Invalid argument."));
+    let max_depth = args.next().unwrap_or(100_000);
+    let max_children = args.next().unwrap_or(3) as u32;
+
+    let generator = Generator {
+        max_depth,
+        min_children: 1,
+        max_children,
+        max_nodes: 1_000_000,
+        ..Generator::default()
+    };
+    let mut rng = StdRng::seed_from_u64(8);
+
+    let tree = match aoc_common::time("Generate", || generator.generate(&mut rng)) {
+        Ok(tree) => tree,
+        Err(err) => panic!("Invalid settings: {}", err),
+    };
+    let nums = aoc_common::time("Encode", || license::encode(&tree));
+    let tree = aoc_common::time("Decode", || license::decode(&nums).unwrap());
+    let sum = aoc_common::time("Sum metadata", || license::metadata_sum(&tree));
+    let sum2 = aoc_common::time("Sum metadata ref", || license::reference_sum(&tree));
+    println!("{} nodes, {} numbers", tree.len(), nums.len());
+    println!("Metadata sum: {}", sum);
+    println!("Reference sum: {}", sum2);
+}
@@ -4,7 +4,7 @@ use fnv::FnvHashSet;
 fn main() {
     let input = DataReader::open(1);
 
-    let numbers = advent2018::time("Read input", || {
+    let numbers = aoc_common::time("Read input", || {
         let mut numbers: Vec<i64> = Vec::new();
         for line in input {
             numbers.push(line.parse().unwrap());
@@ -12,10 +12,10 @@ fn main() {
         numbers
     });
 
-    let sum: i64 = advent2018::time("Sum", || {
+    let sum: i64 = aoc_common::time("Sum", || {
         numbers.iter().cloned().sum()
     });
-    let first_dup = advent2018::time("First duplicate", || {
+    let first_dup = aoc_common::time("First duplicate", || {
         first_duplicate(&numbers[..])
     });
     println!("Part one: {}", sum);
@@ -43,7 +43,7 @@ pub struct LoopingIterator<'a, T: Clone> {
 }
 impl<'a, T: Clone> LoopingIterator<'a, T> {
     pub fn new(values: &'a [T]) -> Self {
-        if values.len() == 0 {
+        if values.is_empty() {
             panic!("Can't loop empty array.");
         }
         LoopingIterator {
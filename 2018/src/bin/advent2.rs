@@ -5,7 +5,7 @@ use fnv::FnvHashSet;
 fn main() {
     let input = DataReader::open(2);
 
-    let ids = advent2018::time("Read input", || {
+    let ids = aoc_common::time("Read input", || {
         let mut ids = Vec::new();
         for line in input {
             ids.push(BoxID::new(line));
@@ -13,11 +13,11 @@ fn main() {
         ids
     });
 
-    let checksum = advent2018::time("Checksum", || {
+    let checksum = aoc_common::time("Checksum", || {
         ChecksumValue::checksum(ids.iter().map(|id| id.checksum()))
     });
 
-    let partial = advent2018::time("Matching ids", || {
+    let partial = aoc_common::time("Matching ids", || {
         find_matching_partial(&ids).expect("No BoxIDs differ by one")
     });
     println!("Part one: {}", checksum);
@@ -26,7 +26,7 @@ fn main() {
 
 fn find_matching_partial<'a>(ids: &'a [BoxID]) -> Option<PartialBoxID<'a>> {
     let mut seen = FnvHashSet::with_capacity_and_hasher(ids.len(), Default::default());
-    let len = match ids.get(0) {
+    let len = match ids.first() {
         Some(id) => id.len(),
         None => return None,
     };
@@ -55,6 +55,9 @@ impl BoxID {
     pub fn len(&self) -> usize {
         self.id.len()
     }
+    pub fn is_empty(&self) -> bool {
+        self.id.is_empty()
+    }
     /// Count how many times every character occurs.
     fn count_characters(&self) -> [usize; 256] {
         let mut count = [0; 256];
@@ -80,16 +83,14 @@ impl BoxID {
     }
 }
 
+#[derive(Default)]
 pub struct ChecksumValue {
     pub has_double: bool,
     pub has_triple: bool,
 }
 impl ChecksumValue {
     pub fn new() -> Self {
-        ChecksumValue {
-            has_double: false,
-            has_triple: false,
-        }
+        ChecksumValue::default()
     }
     pub fn checksum<I>(iter: I) -> u64
     where
@@ -1,24 +1,24 @@
-use advent2018::{DataReader, TextIOLine};
-use text_io::*;
+use advent2018::DataReader;
+use aoc_common::parse::scan;
 
 use prefix_sum::sum2d::{PrefixSum2D, Rect, Buf2D};
 
 fn main() {
     let input = DataReader::open(3);
 
-    let claims = advent2018::time("Read input", move || {
+    let claims = aoc_common::time("Read input", move || {
         let mut claims = Vec::new();
-        for line in input.text_io() {
-            claims.push(Claim::new(line));
+        for line in input {
+            claims.push(Claim::new(&line));
         }
         claims
     });
 
-    let (overlaps, counts) = advent2018::time("Count overlaps", || {
+    let (overlaps, counts) = aoc_common::time("Count overlaps", || {
         count_overlap_at_least_two(&claims)
     });
 
-    let lonely = advent2018::time("Find lonely", || {
+    let lonely = aoc_common::time("Find lonely", || {
         find_lonely_claim(&counts, &claims)
     });
     println!("Part one: {}", overlaps);
@@ -79,9 +79,8 @@ pub struct Claim {
     pub rect: Rect,
 }
 impl Claim {
-    pub fn new(mut s: TextIOLine) -> Self {
-        let (claim_id, x, y, width, height);
-        scan!(s => "#{} @ {},{}: {}x{}", claim_id, x, y, width, height);
+    pub fn new(s: &str) -> Self {
+        let (claim_id, x, y, width, height) = scan("#{} @ {},{}: {}x{}", s).unwrap();
         Claim {
             claim_id,
             rect: Rect::new(x, y, width, height),
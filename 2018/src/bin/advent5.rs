@@ -1,20 +1,21 @@
 use advent2018::DataReader;
+use aoc_common::bitset::BitSet256;
 use std::collections::VecDeque;
 
 fn main() {
     let mut input = DataReader::open(5);
-    let mut polymer = advent2018::time("Read input", || {
+    let mut polymer = aoc_common::time("Read input", || {
         Polymer::new(input.next().unwrap())
     });
 
-    let alive = advent2018::time("Fully react", || {
+    let alive = aoc_common::time("Fully react", || {
         fully_react(&mut polymer)
     });
 
-    let best_length = advent2018::time("Fully react letter", || {
-        let mut min = std::usize::MAX;
-        for letter in ('a' as u8) ..= ('z' as u8) {
-            polymer.revive_all_but(letter);
+    let best_length = aoc_common::time("Fully react letter", || {
+        let mut min = usize::MAX;
+        for letter in polymer.letters().iter() {
+            polymer.revive_all_but(letter as u8);
             let len = fully_react(&mut polymer);
             if len < min {
                 min = len;
@@ -64,7 +65,7 @@ fn fully_react(polymer: &mut Polymer) -> usize {
 
 #[inline]
 fn reacts(a: u8, b: u8) -> bool {
-    let space = ' ' as u8;
+    let space = b' ';
     (a ^ space) == b
 }
 
@@ -84,6 +85,10 @@ impl Polymer {
     pub fn len(&self) -> usize {
         self.codes.len()
     }
+    /// The lowercase letters of the units in the polymer.
+    pub fn letters(&self) -> BitSet256 {
+        self.codes.iter().map(|code| code.to_ascii_lowercase() as usize).collect()
+    }
     pub fn revive_all_but(&mut self, dead: u8) {
         for (ptr, code) in self.destroyed.iter_mut().zip(self.codes.iter().cloned()) {
             *ptr = code == dead || reacts(code, dead);
@@ -1,24 +1,30 @@
+//! Pass a file name to also draw the areas there, as an image if it ends
+//! in `.png` or `.ppm` and with terminal colors otherwise. Infinite areas
+//! are drawn darker and ties in black.
+
 use advent2018::DataReader;
-use std::collections::VecDeque;
 
-use fnv::FnvHashMap;
-use text_io::*;
+use aoc_common::grid::Grid;
+use aoc_common::point::Point;
+use aoc_common::render::{self, Rgb};
+use aoc_common::search::{bfs_multi, Owner, Search};
+use std::path::PathBuf;
 
 fn main() {
     let input = DataReader::open(6);
 
-    let points = advent2018::time("Read input", || {
+    let points = aoc_common::time("Read input", || {
         let mut points = Vec::new();
-        for mut line in input.text_io() {
-            let (x, y);
-            scan!(line => "{}, {}", x, y);
-            points.push(OrigPoint(x, y, points.len()));
+        for line in input {
+            let point: Point<i32> = line.parse().unwrap();
+            points.push(point);
         }
         points
     });
 
-    let max_size = advent2018::time("Dijkstra", || {
-        let sizes = find_sizes(&points);
+    let search = aoc_common::time("Search", || search_areas(&points));
+    let sizes = find_sizes(&points, &search);
+    let max_size = aoc_common::time("Largest area", || {
         let mut max_size = 0;
         for size in &sizes {
             if let Some(size) = *size {
@@ -29,34 +35,62 @@ fn main() {
         }
         max_size
     });
-    let shared = advent2018::time("Region and", || {
+    let shared = aoc_common::time("Region and", || {
         count_shared_region(&points, 10000)
     });
 
     println!("Part one: {}", max_size);
     println!("Part two: {}", shared);
+
+    if let Some(path) = std::env::args_os().nth(1).map(PathBuf::from) {
+        match draw_areas(&points, &search, &sizes) {
+            Some(frame) => if let Err(err) = render::write(&path, &frame) {
+                panic!("Cannot write {}: {}", path.display(), err);
+            },
+            None => println!("There are no areas to draw."),
+        }
+    }
 }
 
-fn count_shared_region(points: &[OrigPoint], max_dist: i32) -> usize {
+fn draw_areas(points: &[Point<i32>], search: &Search<Point<i32>, usize>, sizes: &[Option<usize>]) -> Option<Grid<Rgb>> {
+    let bounding_box = max_xy(points);
+    let cells = search.owners()
+        .filter(|(point, _)| {
+            (0..=bounding_box.0).contains(&point.x) && (0..=bounding_box.1).contains(&point.y)
+        })
+        .map(|(&point, owner)| {
+            let color = match owner {
+                Owner::Start(i) if sizes[i].is_some() => Rgb::palette(i),
+                Owner::Start(i) => Rgb::palette(i).lerp(Rgb::BLACK, 0.6),
+                Owner::Tie => Rgb::BLACK,
+            };
+            (point.map(i64::from), color)
+        })
+        .collect::<Vec<_>>();
+    let centers = points.iter().map(|point| (point.map(i64::from), Rgb::WHITE));
+    render::plot(cells.into_iter().chain(centers), Rgb::BLACK).map(|(frame, _)| frame)
+}
+
+fn count_shared_region(points: &[Point<i32>], max_dist: i32) -> usize {
     let max_dist = max_dist-1;
-    if points.len() == 0 {
+    if points.is_empty() {
         return 0;
     }
     let mut region = Vec::new();
     let point1 = points[0];
     let points = &points[1..];
-    for x in point1.0 - max_dist ..= point1.0 + max_dist {
-        let y_max = max_dist - (point1.0 - x).abs();
-        for y in point1.1 - y_max ..= point1.1 + y_max {
-            let p = Point(x, y);
-            region.push((p, p.dist(point1)));
+    for x in point1.x - max_dist ..= point1.x + max_dist {
+        let y_max = max_dist - (point1.x - x).abs();
+        for y in point1.y - y_max ..= point1.y + y_max {
+            let p = Point::new(x, y);
+            region.push((p, p.manhattan(point1)));
         }
     }
 
     for point in points {
         for i in (0..region.len()).rev() {
             let (p, sum_dist) = region[i];
-            let dist = p.dist(point);
+            let dist = p.manhattan(*point);
             let sum_dist = sum_dist + dist;
             if max_dist < sum_dist {
                 region.swap_remove(i);
@@ -69,116 +103,53 @@ fn count_shared_region(points: &[OrigPoint], max_dist: i32) -> usize {
     region.len()
 }
 
-fn find_sizes(points: &[OrigPoint]) -> Vec<Option<usize>> {
+/// The closest point to every position, out to somewhat beyond the
+/// bounding box.
+fn search_areas(points: &[Point<i32>]) -> Search<Point<i32>, usize> {
+    let bounding_box = max_xy(points);
+    // Areas that reach past the bounding box are infinite, so the search
+    // doesn't go further than that.
+    bfs_multi(points.iter().copied(), |&point: &Point<i32>| {
+        let outside = is_outside(point, bounding_box);
+        point.neighbors4().filter(move |_| !outside)
+    })
+}
+
+/// The size of the area around every point, or `None` if it is infinite.
+fn find_sizes(points: &[Point<i32>], search: &Search<Point<i32>, usize>) -> Vec<Option<usize>> {
     let bounding_box = max_xy(points);
-    enum Visited {
-        SeenOnce(OrigPoint, usize),
-        SeenMultiple,
-    };
     let mut is_inf = vec![false; points.len()];
-    let mut visited: FnvHashMap<Point, Visited> = FnvHashMap::default();
-    let mut stack = VecDeque::new();
-    for &point in points {
-        stack.push_back((0, point, point.into()));
-    }
-    // dijkstra
-    while let Some((dist, orig, point)) = stack.pop_front() {
-        match visited.get(&point) {
-            Some(Visited::SeenOnce(orig2, dist2)) => {
-                if *dist2 == dist {
-                    if *orig2 != orig {
-                        visited.insert(point, Visited::SeenMultiple);
-                    }
-                }
-            },
-            Some(Visited::SeenMultiple) => {},
-            None => {
-                visited.insert(point, Visited::SeenOnce(orig, dist));
-                if point.is_inf(bounding_box) {
-                    is_inf[orig.2] = true;
-                } else {
-                    stack.push_back((dist+1, orig, point.left()));
-                    stack.push_back((dist+1, orig, point.right()));
-                    stack.push_back((dist+1, orig, point.up()));
-                    stack.push_back((dist+1, orig, point.down()));
-                }
-            },
-        }
-    }
-    let mut counts = Vec::new();
-    for point in points {
-        if is_inf[point.2] {
-            counts.push(None);
-        } else {
-            counts.push(Some(0));
-        }
-    }
+    let mut counts = vec![0; points.len()];
     // count how many are in each area
-    for (_point, visited) in visited {
-        match visited {
-            Visited::SeenOnce(orig, _dist) => {
-                if let Some(count) = counts[orig.2] {
-                    counts[orig.2] = Some(count + 1);
-                }
-            },
-            Visited::SeenMultiple => {},
+    for (&point, owner) in search.owners() {
+        if let Owner::Start(i) = owner {
+            counts[i] += 1;
+            if is_outside(point, bounding_box) {
+                is_inf[i] = true;
+            }
         }
     }
-    counts
+    counts.into_iter()
+        .zip(is_inf)
+        .map(|(count, is_inf)| if is_inf { None } else { Some(count) })
+        .collect()
 }
 
-
-
-
-
-fn max_xy(points: &[OrigPoint]) -> (i32, i32) {
+fn max_xy(points: &[Point<i32>]) -> (i32, i32) {
     let mut maxx = 0;
     let mut maxy = 0;
     for point in points {
-        if maxx < point.0 {
-            maxx = point.0;
+        if maxx < point.x {
+            maxx = point.x;
         }
-        if maxy < point.1 {
-            maxy = point.1;
+        if maxy < point.y {
+            maxy = point.y;
         }
     }
     (maxx, maxy)
 }
 
-#[derive(Copy,Clone,PartialEq,Eq,Debug,Hash,PartialOrd,Ord)]
-struct Point(i32, i32);
-#[derive(Copy,Clone,PartialEq,Eq,Debug,Hash,PartialOrd,Ord)]
-struct OrigPoint(i32, i32, usize);
-
-impl Point {
-    pub fn dist(self, other: impl Into<Point>) -> i32 {
-        let other = other.into();
-        (self.0 - other.0).abs() + (self.1 - other.1).abs()
-    }
-    pub fn is_inf(self, bounding_box: (i32, i32)) -> bool {
-        self.0 < -bounding_box.0 || self.1 < -bounding_box.1
-            || self.0 > 2*bounding_box.0 || self.1 > 2*bounding_box.1
-    }
-    pub fn left(self) -> Point {
-        Point(self.0 - 1, self.1)
-    }
-    pub fn right(self) -> Point {
-        Point(self.0 + 1, self.1)
-    }
-    pub fn up(self) -> Point {
-        Point(self.0, self.1 - 1)
-    }
-    pub fn down(self) -> Point {
-        Point(self.0, self.1 + 1)
-    }
-}
-impl From<OrigPoint> for Point {
-    fn from(o: OrigPoint) -> Point {
-        Point(o.0, o.1)
-    }
-}
-impl From<&OrigPoint> for Point {
-    fn from(o: &OrigPoint) -> Point {
-        Point(o.0, o.1)
-    }
+fn is_outside(point: Point<i32>, bounding_box: (i32, i32)) -> bool {
+    point.x < -bounding_box.0 || point.y < -bounding_box.1
+        || point.x > 2*bounding_box.0 || point.y > 2*bounding_box.1
 }
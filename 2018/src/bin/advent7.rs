@@ -1,39 +1,69 @@
+//! Pass a file name to also write the dependency graph there, as DOT if it
+//! ends in `.dot` and as an indented tree otherwise. The nodes are numbered
+//! in the order of part one.
+
 use advent2018::DataReader;
 
-use std::collections::BTreeSet;
 use std::collections::BinaryHeap;
+use aoc_common::bitset::BitSet256;
+use aoc_common::diagram::{self, Highlight};
+use aoc_common::graph::Graph;
+use aoc_common::parse::scan;
 use fnv::FnvHashMap;
-use text_io::*;
 
 use std::cmp;
 use std::iter::FromIterator;
+use std::path::PathBuf;
 
 fn main() {
     let input = DataReader::open(7);
 
-    let deps = advent2018::time("Read input", || {
+    let deps = aoc_common::time("Read input", || {
         let mut deps = Vec::new();
-        for mut line in input.text_io() {
-            let task: char;
-            let dep: char;
-            scan!(line =>
-                  "Step {} must be finished before step {} can begin.", dep, task);
+        for line in input {
+            let (dep, task): (char, char) =
+                scan("Step {} must be finished before step {} can begin.", &line).unwrap();
             deps.push((task as u8, dep as u8));
         }
         deps
     });
 
-    let sort = advent2018::time("Topological sort", || {
+    let sort = aoc_common::time("Topological sort", || {
         let sorter = TopologicalSorter::new(&deps);
         String::from_iter(sorter.map(|byte| byte as char))
     });
-    let finish = advent2018::time("Topological timing", || {
+    let finish = aoc_common::time("Topological timing", || {
         let timer = WorkerTimer::new(&deps, 5);
         timer.get_finish_instant().0
     });
 
     println!("Part one: {}", sort);
     println!("Part two: {}", finish);
+
+    if let Some(path) = std::env::args_os().nth(1).map(PathBuf::from) {
+        let graph = dependency_graph(&deps);
+        let order = sort.chars().map(|task| graph.id(&task.to_string()).unwrap().index());
+        if let Err(err) = diagram::write(&path, &graph, &Highlight::order(order)) {
+            panic!("Cannot write {}: {}", path.display(), err);
+        }
+    }
+}
+
+/// The graph with an edge from every step to the steps waiting for it,
+/// weighted by how long the waiting step takes.
+fn dependency_graph(dep_list: &[(u8, u8)]) -> Graph<u32> {
+    let mut graph = Graph::directed();
+    let mut tasks: Vec<u8> = dep_list.iter().flat_map(|&(task, dep)| [task, dep]).collect();
+    tasks.sort_unstable();
+    tasks.dedup();
+    for task in tasks {
+        graph.node(&(task as char).to_string());
+    }
+    for &(task, depends_on) in dep_list {
+        let duration = Instant(0, None).add(task).0;
+        graph.add_named_edge(&(depends_on as char).to_string(), &(task as char).to_string(), duration);
+    }
+    graph
 }
 
 struct WorkerTimer {
@@ -105,9 +135,9 @@ impl WorkerTimer {
 struct Instant(u32, Option<u8>);
 impl Instant {
     pub fn add(&self, task: u8) -> Instant {
-        assert!(task <= 'Z' as u8);
-        assert!(task >= 'A' as u8);
-        let time = (task - ('A' as u8)) as u32 + 61;
+        assert!(task <= b'Z');
+        assert!(task >= b'A');
+        let time = (task - b'A') as u32 + 61;
         Instant(self.0 + time, Some(task))
     }
 }
@@ -116,7 +146,7 @@ impl Instant {
 // smallest instant is considered the largest.
 impl PartialOrd for Instant {
     fn partial_cmp(&self, other: &Instant) -> Option<cmp::Ordering> {
-        other.0.partial_cmp(&self.0)
+        Some(self.cmp(other))
     }
 }
 impl Ord for Instant {
@@ -128,36 +158,32 @@ impl Ord for Instant {
 struct TopologicalSorter {
     deps_on_me: FnvHashMap<u8, Vec<u8>>,
     dependency_count: FnvHashMap<u8, usize>,
-    available: BTreeSet<u8>,
+    available: BitSet256,
 }
 impl TopologicalSorter {
     pub fn new(dep_list: &[(u8, u8)]) -> TopologicalSorter {
         let mut deps_on_me = FnvHashMap::default();
         let mut dependency_count = FnvHashMap::default();
-        let mut available = BTreeSet::new();
+        let mut available = BitSet256::new();
         for &(task, depends_on) in dep_list {
             let dep_count_ptr = dependency_count.entry(task).or_insert(0usize);
             *dep_count_ptr += 1;
 
             deps_on_me.entry(depends_on).or_insert(Vec::new()).push(task);
 
-            available.insert(depends_on);
+            available.insert(depends_on as usize);
         }
         for &(task, _depends_on) in dep_list {
-            available.remove(&task);
+            available.remove(task as usize);
         }
         TopologicalSorter {
-            deps_on_me: deps_on_me,
+            deps_on_me,
             dependency_count,
             available,
         }
     }
     fn start_item(&mut self) -> Option<u8> {
-        let min_avail = self.available.iter().cloned().next();
-        if let Some(min) = min_avail {
-            self.available.remove(&min);
-        }
-        min_avail
+        self.available.pop_first().map(|min| min as u8)
     }
     fn remove_item(&mut self, item: u8) {
         if let Some(list) = self.deps_on_me.get(&item) {
@@ -165,7 +191,7 @@ impl TopologicalSorter {
                 let count_ref = self.dependency_count.get_mut(&dep).unwrap();
                 *count_ref -= 1;
                 if *count_ref == 0 {
-                    self.available.insert(dep);
+                    self.available.insert(dep as usize);
                 }
             }
         }
@@ -174,11 +200,8 @@ impl TopologicalSorter {
 impl Iterator for TopologicalSorter {
     type Item = u8;
     fn next(&mut self) -> Option<u8> {
-        let min_avail = self.available.iter().cloned().next();
-        if let Some(min) = min_avail {
-            self.available.remove(&min);
-            self.remove_item(min);
-        }
-        min_avail
+        let min = self.start_item()?;
+        self.remove_item(min);
+        Some(min)
     }
 }
@@ -1,72 +1,37 @@
-use advent2018::DataReader;
+//! Pass a file name to also write the tree there, as DOT if it ends in
+//! `.dot` and as an indented tree otherwise. The nodes counted by part two
+//! are highlighted.
 
-use std::collections::BTreeSet;
-use std::collections::BinaryHeap;
-use fnv::FnvHashMap;
-use text_io::*;
+use advent2018::DataReader;
+use advent2018::license;
+use aoc_common::diagram::{self, Highlight};
+use std::path::PathBuf;
 
 fn main() {
     let mut input = DataReader::open(8);
 
-    let input = advent2018::time("Read input", || {
+    let input = aoc_common::time("Read input", || {
         let line = input.next().unwrap();
         let nums: Vec<u32> = line.split(' ').map(|n| n.parse().unwrap()).collect();
-        let (node, remain) = Node::parse(&nums);
-        assert_eq!(remain.len(), 0);
-        node
+        match license::decode(&nums) {
+            Ok(tree) => tree,
+            Err(err) => panic!("Invalid license file: {}", err),
+        }
     });
 
-    let sum = advent2018::time("Sum metadata", || {
-        input.metadata_sum()
+    let sum = aoc_common::time("Sum metadata", || {
+        license::metadata_sum(&input)
     });
-    let sum2 = advent2018::time("Sum metadata ref", || {
-        input.reference_sum()
+    let sum2 = aoc_common::time("Sum metadata ref", || {
+        license::reference_sum(&input)
     });
     println!("Part one: {}", sum);
     println!("Part two: {}", sum2);
-}
 
-struct Node {
-    children: Vec<Node>,
-    metadata: Vec<u32>,
-}
-impl Node {
-    pub fn parse(slice: &[u32]) -> (Node, &[u32]) {
-        let child_len = slice[0] as usize;
-        let metadata_len = slice[1] as usize;
-        let mut slice = &slice[2..];
-
-        let mut children = Vec::with_capacity(child_len);
-        for _ in 0..child_len {
-            let res = Node::parse(slice);
-            children.push(res.0);
-            slice = res.1;
-        }
-        let metadata = Vec::from(&slice[0..metadata_len]);
-        (Node {
-            children,
-            metadata,
-        }, &slice[metadata_len..])
-    }
-    fn metadata_sum(&self) -> u32 {
-        let selfdata: u32 = self.metadata.iter().cloned().sum();
-        let childdata: u32 = self.children.iter().map(|child| child.metadata_sum()).sum();
-        selfdata + childdata
-    }
-    fn reference_sum(&self) -> u32 {
-        if self.children.len() == 0 {
-            self.metadata.iter().cloned().sum()
-        } else {
-            let sums: Vec<u32> = self.children.iter()
-                .map(|child| child.reference_sum()).collect();
-            let mut sum = 0;
-            for meta in &self.metadata {
-                if *meta == 0 { continue; }
-                if let Some(v) = sums.get((meta-1) as usize) {
-                    sum += v;
-                }
-            }
-            sum
+    if let Some(path) = std::env::args_os().nth(1).map(PathBuf::from) {
+        let referenced = license::referenced_nodes(&input).into_iter().map(|node| node.index());
+        if let Err(err) = diagram::write(&path, &input, &Highlight::nodes(referenced)) {
+            panic!("Cannot write {}: {}", path.display(), err);
         }
     }
 }
@@ -1,31 +1,63 @@
+//! Pass a directory to also draw the first few turns there as numbered PNG
+//! frames, or `-` to play them in the terminal. Each frame shows the circle
+//! starting from marble 0, with the current marble in white.
+
 use advent2018::DataReader;
+use aoc_common::grid::Grid;
+use aoc_common::point::Point;
+use aoc_common::render::{FrameSequence, ImageFormat, Rgb};
 
-use text_io::*;
 use std::cmp;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+const ANIMATED_TURNS: usize = 200;
 
 fn main() {
-    let input = DataReader::open(9);
+    let mut input = DataReader::open(9);
 
-    let (players, points) = advent2018::time("Read input", || {
-        let mut line = input.text_io().next().unwrap();
-        let pl: usize;
-        let po: usize;
-        scan!(line => "{} players; last marble is worth {} points", pl, po);
-        (pl, po)
+    let Settings { players, points } = aoc_common::time("Read input", || {
+        input.next().unwrap().parse().unwrap()
     });
 
-    let high_score = advent2018::time("Play game", || {
+    let high_score = aoc_common::time("Play game", || {
         solve_part_one(players, points)
     });
     // good thing the solution runs in linear time
-    let high_score_large = advent2018::time("Play long game", || {
+    let high_score_large = aoc_common::time("Play long game", || {
         solve_part_one(players, 100*points)
     });
     println!("Part one: {}", high_score);
     println!("Part one: {}", high_score_large);
 
+    if let Some(target) = std::env::args_os().nth(1) {
+        let frames = if target == "-" {
+            Ok(FrameSequence::terminal(Duration::from_millis(50)))
+        } else {
+            FrameSequence::files(Path::new(&target), "marbles", ImageFormat::Png).map(|frames| frames.scale(8))
+        };
+        if let Err(err) = frames.and_then(animate) {
+            panic!("Cannot draw the game: {}", err);
+        }
+    }
 }
 
+fn animate(mut frames: FrameSequence) -> io::Result<()> {
+    let mut game = MarbleGame::new();
+    for _ in 0..ANIMATED_TURNS {
+        game.insert_next_marble();
+        frames.push(&game.draw(ANIMATED_TURNS + 1))?;
+    }
+    Ok(())
+}
+
+struct Settings {
+    players: usize,
+    points: usize,
+}
+aoc_common::from_pattern!(Settings, "{} players; last marble is worth {} points", players, points);
+
 pub fn solve_part_one(players: usize, marbles: usize) -> usize {
     let mut scores = vec![0; players];
     let mut player = 0;
@@ -72,7 +104,7 @@ impl MarbleGame {
                 self.marbles.push(CircleSpot {
                     left: i,
                     right: i,
-                    marble: MarbleId(std::usize::MAX),
+                    marble: MarbleId(usize::MAX),
                 });
                 i
             },
@@ -113,16 +145,26 @@ impl MarbleGame {
             0
         }
     }
-    #[allow(dead_code)]
-    pub fn debug(&self) {
-        let mut i = self.current_marble;
-        print!("{} ", self.marbles[i].marble.0);
-        i = self.go_right(i);
-        while i != self.current_marble {
-            print!("{} ", self.marbles[i].marble.0);
+    /// The circle as a row of `width` pixels, clockwise from marble 0.
+    /// Marbles get brighter as their number grows.
+    pub fn draw(&self, width: usize) -> Grid<Rgb> {
+        let mut frame = Grid::new(width, 1, Rgb::BLACK);
+        let dark = Rgb(40, 0, 90);
+        let bright = Rgb(255, 190, 0);
+        let mut i = 0;
+        for x in 0..width {
+            let marble = self.marbles[i].marble.0;
+            frame[Point::new(x, 0)] = if i == self.current_marble {
+                Rgb::WHITE
+            } else {
+                dark.lerp(bright, marble as f64 / self.next_marble as f64)
+            };
             i = self.go_right(i);
+            if i == 0 {
+                break;
+            }
         }
-        println!();
+        frame
     }
 }
 // left is counter-clockwise
@@ -138,13 +180,13 @@ struct CircleSpot {
 struct MarbleId(usize);
 impl MarbleId {
     fn is_special(self) -> bool {
-        self.0 % 23 == 0
+        self.0.is_multiple_of(23)
     }
 }
 // reversed ordering
 impl PartialOrd for MarbleId {
     fn partial_cmp(&self, other: &MarbleId) -> Option<cmp::Ordering> {
-        other.0.partial_cmp(&self.0)
+        Some(self.cmp(other))
     }
 }
 impl Ord for MarbleId {
@@ -0,0 +1,440 @@
+//! Parser and queries for the guard sleep logs of day 4.
+//!
+//! A log is a list of timestamped events. After sorting them by time, the
+//! events are folded into [`Shift`]s, each of which records which minutes of
+//! the midnight hour the guard on duty spent asleep.
+
+use crate::timestamp::{Date, Timestamp};
+use fnv::FnvHashMap;
+use std::fmt;
+
+/// Number of minutes in the midnight hour, the only hour a guard sleeps in.
+pub const MINUTES: usize = 60;
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash,PartialOrd,Ord)]
+pub struct GuardId(pub u32);
+
+impl fmt::Display for GuardId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub enum Event {
+    BeginShift(GuardId),
+    FallAsleep,
+    WakeUp,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::BeginShift(id) => write!(f, "Guard #{} begins shift", id),
+            Event::FallAsleep => write!(f, "falls asleep"),
+            Event::WakeUp => write!(f, "wakes up"),
+        }
+    }
+}
+
+/// A single parsed line of the log.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct LogEntry {
+    /// The line number in the input, starting at one.
+    pub line: usize,
+    pub time: Timestamp,
+    pub event: Event,
+}
+
+impl LogEntry {
+    /// Parse a line such as `[1518-11-01 00:05] falls asleep`.
+    pub fn parse(line: usize, s: &str) -> Result<LogEntry, LogError> {
+        let malformed = |what: &str| LogError {
+            line,
+            kind: LogErrorKind::Malformed(what.to_string()),
+        };
+
+        let s = s.trim();
+        let (time, event) = s.strip_prefix('[')
+            .and_then(|s| s.split_once(']'))
+            .ok_or_else(|| malformed("expected a timestamp in brackets"))?;
+
+        let time = time.parse().map_err(|_| malformed("invalid timestamp"))?;
+
+        let event = match event.trim() {
+            "falls asleep" => Event::FallAsleep,
+            "wakes up" => Event::WakeUp,
+            other => {
+                let id = other.strip_prefix("Guard #")
+                    .and_then(|s| s.strip_suffix(" begins shift"))
+                    .and_then(|id| id.parse().ok())
+                    .ok_or_else(|| malformed("unknown event"))?;
+                Event::BeginShift(GuardId(id))
+            },
+        };
+
+        Ok(LogEntry { line, time, event })
+    }
+}
+
+/// The minutes of the midnight hour during which a guard was asleep.
+#[derive(Copy,Clone,Debug,Default,Eq,PartialEq,Hash)]
+pub struct SleepMinutes(u64);
+
+impl SleepMinutes {
+    pub fn is_asleep(self, minute: usize) -> bool {
+        minute < MINUTES && self.0 & (1 << minute) != 0
+    }
+    /// Mark the minutes in `from..to` as asleep.
+    ///
+    /// Panics if `to` is past the end of the hour.
+    pub fn add_nap(&mut self, from: usize, to: usize) {
+        assert!(to <= MINUTES, "nap until minute {} is past the end of the hour", to);
+        for minute in from..to {
+            self.0 |= 1 << minute;
+        }
+    }
+    /// The total number of minutes spent asleep.
+    pub fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+    pub fn iter(self) -> impl Iterator<Item = usize> {
+        (0..MINUTES).filter(move |&minute| self.is_asleep(minute))
+    }
+}
+
+/// One guard's shift.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Shift {
+    pub guard: GuardId,
+    /// When the guard began the shift. Shifts may begin shortly before
+    /// midnight.
+    pub start: Timestamp,
+    pub asleep: SleepMinutes,
+}
+
+impl Shift {
+    /// The date of the midnight hour this shift covers.
+    pub fn date(&self) -> Date {
+        self.start.nearest_midnight()
+    }
+    pub fn starts_before_midnight(&self) -> bool {
+        self.start.hour() != 0
+    }
+}
+
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct LogError {
+    /// The line number of the offending entry, starting at one.
+    pub line: usize,
+    pub kind: LogErrorKind,
+}
+
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum LogErrorKind {
+    Malformed(String),
+    NoGuardOnDuty,
+    AlreadyAsleep(GuardId),
+    AlreadyAwake(GuardId),
+    AsleepAtEndOfShift(GuardId),
+    /// Guards only sleep during the midnight hour.
+    OutsideMidnightHour,
+    /// The guard wakes up in the minute they fell asleep or during a later
+    /// midnight hour, so the nap covers no minutes of a single hour.
+    InvalidNap(GuardId),
+}
+
+impl fmt::Display for LogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            LogErrorKind::Malformed(what) => write!(f, "malformed entry: {}", what),
+            LogErrorKind::NoGuardOnDuty => write!(f, "no guard is on duty"),
+            LogErrorKind::AlreadyAsleep(id) => write!(f, "guard #{} is already asleep", id),
+            LogErrorKind::AlreadyAwake(id) => write!(f, "guard #{} is already awake", id),
+            LogErrorKind::AsleepAtEndOfShift(id) => {
+                write!(f, "guard #{} is asleep at the end of the shift", id)
+            },
+            LogErrorKind::OutsideMidnightHour => {
+                write!(f, "guards can only sleep during the midnight hour")
+            },
+            LogErrorKind::InvalidNap(id) => {
+                write!(f, "guard #{} does not wake up later in the hour they fell asleep", id)
+            },
+        }
+    }
+}
+
+impl std::error::Error for LogError {}
+
+/// A validated guard log, split into shifts.
+#[derive(Clone,Debug,Default)]
+pub struct GuardLog {
+    shifts: Vec<Shift>,
+}
+
+impl GuardLog {
+    /// Parse the lines of a log. The lines may be in any order. Empty lines
+    /// are skipped, but still counted when reporting line numbers.
+    pub fn parse<I>(lines: I) -> Result<GuardLog, LogError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut entries = Vec::new();
+        for (i, line) in lines.into_iter().enumerate() {
+            let line = line.as_ref();
+            if line.trim().is_empty() {
+                continue;
+            }
+            entries.push(LogEntry::parse(i + 1, line)?);
+        }
+        GuardLog::from_entries(entries)
+    }
+
+    /// Build the log from entries in any order.
+    pub fn from_entries(mut entries: Vec<LogEntry>) -> Result<GuardLog, LogError> {
+        entries.sort_by_key(|entry| (entry.time, entry.line));
+
+        enum State {
+            NoGuard,
+            Awake(Shift),
+            /// The time the guard fell asleep and the line saying so.
+            Asleep(Shift, Timestamp, usize),
+        }
+
+        let mut shifts = Vec::new();
+        let mut state = State::NoGuard;
+        for entry in &entries {
+            let error = |kind| Err(LogError { line: entry.line, kind });
+            state = match (entry.event, state) {
+                (Event::BeginShift(_), State::Asleep(shift, _, _)) => {
+                    return error(LogErrorKind::AsleepAtEndOfShift(shift.guard));
+                },
+                (Event::BeginShift(guard), prev) => {
+                    if let State::Awake(shift) = prev {
+                        shifts.push(shift);
+                    }
+                    State::Awake(Shift {
+                        guard,
+                        start: entry.time,
+                        asleep: SleepMinutes::default(),
+                    })
+                },
+                (_, State::NoGuard) => return error(LogErrorKind::NoGuardOnDuty),
+                (_, _) if entry.time.hour() != 0 => {
+                    return error(LogErrorKind::OutsideMidnightHour);
+                },
+                (Event::FallAsleep, State::Awake(shift)) => {
+                    State::Asleep(shift, entry.time, entry.line)
+                },
+                (Event::FallAsleep, State::Asleep(shift, _, _)) => {
+                    return error(LogErrorKind::AlreadyAsleep(shift.guard));
+                },
+                (Event::WakeUp, State::Awake(shift)) => {
+                    return error(LogErrorKind::AlreadyAwake(shift.guard));
+                },
+                (Event::WakeUp, State::Asleep(mut shift, since, _)) => {
+                    if entry.time.date() != since.date() || entry.time.minute() <= since.minute() {
+                        return error(LogErrorKind::InvalidNap(shift.guard));
+                    }
+                    shift.asleep.add_nap(since.minute() as usize, entry.time.minute() as usize);
+                    State::Awake(shift)
+                },
+            };
+        }
+        match state {
+            State::NoGuard => {},
+            State::Awake(shift) => shifts.push(shift),
+            State::Asleep(shift, _, line) => {
+                return Err(LogError {
+                    line,
+                    kind: LogErrorKind::AsleepAtEndOfShift(shift.guard),
+                });
+            },
+        }
+
+        Ok(GuardLog { shifts })
+    }
+
+    /// The shifts in chronological order.
+    pub fn shifts(&self) -> &[Shift] {
+        &self.shifts
+    }
+
+    pub fn guard_shifts(&self, guard: GuardId) -> impl Iterator<Item = &Shift> {
+        self.shifts.iter().filter(move |shift| shift.guard == guard)
+    }
+
+    pub fn minutes_asleep(&self, guard: GuardId) -> u32 {
+        self.guard_shifts(guard).map(|shift| shift.asleep.count()).sum()
+    }
+
+    /// How many shifts the guard spent asleep at each minute.
+    pub fn histogram(&self, guard: GuardId) -> [u32; MINUTES] {
+        let mut hist = [0; MINUTES];
+        for shift in self.guard_shifts(guard) {
+            for minute in shift.asleep.iter() {
+                hist[minute] += 1;
+            }
+        }
+        hist
+    }
+
+    /// The histogram of every guard in the log.
+    pub fn histograms(&self) -> FnvHashMap<GuardId, [u32; MINUTES]> {
+        let mut map = FnvHashMap::default();
+        for shift in &self.shifts {
+            let hist = map.entry(shift.guard).or_insert([0; MINUTES]);
+            for minute in shift.asleep.iter() {
+                hist[minute] += 1;
+            }
+        }
+        map
+    }
+
+    /// The `k` minutes the guard was most often asleep at, as pairs of minute
+    /// and count. Minutes the guard was never asleep at are not included.
+    /// Ties are broken by the earliest minute.
+    pub fn sleepiest_minutes(&self, guard: GuardId, k: usize) -> Vec<(usize, u32)> {
+        let mut minutes: Vec<(usize, u32)> = self.histogram(guard)
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|&(_, count)| count > 0)
+            .collect();
+        minutes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        minutes.truncate(k);
+        minutes
+    }
+
+    /// The guard with the most minutes asleep in total.
+    pub fn sleepiest_guard(&self) -> Option<GuardId> {
+        let mut totals: FnvHashMap<GuardId, u32> = FnvHashMap::default();
+        for shift in &self.shifts {
+            *totals.entry(shift.guard).or_insert(0) += shift.asleep.count();
+        }
+        totals.into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+            .map(|(guard, _)| guard)
+    }
+
+    /// The guard and minute such that the guard was asleep at that minute
+    /// more often than any guard at any other minute, along with the count.
+    /// Returns `None` if no guard ever fell asleep.
+    pub fn most_frequent_minute(&self) -> Option<(GuardId, usize, u32)> {
+        let mut best: Option<(GuardId, usize, u32)> = None;
+        for (guard, hist) in self.histograms() {
+            for (minute, &count) in hist.iter().enumerate() {
+                let better = count > 0 && match best {
+                    None => true,
+                    Some((bguard, bminute, bcount)) => {
+                        (count, bguard, bminute) > (bcount, guard, minute)
+                    },
+                };
+                if better {
+                    best = Some((guard, minute, count));
+                }
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] falls asleep
+[1518-11-01 00:55] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep
+[1518-11-02 00:50] wakes up
+[1518-11-03 00:05] Guard #10 begins shift
+[1518-11-03 00:24] falls asleep
+[1518-11-03 00:29] wakes up
+[1518-11-04 00:02] Guard #99 begins shift
+[1518-11-04 00:36] falls asleep
+[1518-11-04 00:46] wakes up
+[1518-11-05 00:03] Guard #99 begins shift
+[1518-11-05 00:45] falls asleep
+[1518-11-05 00:55] wakes up";
+
+    #[test]
+    fn example() {
+        let mut lines: Vec<&str> = EXAMPLE.lines().collect();
+        lines.reverse();
+        let log = GuardLog::parse(lines).unwrap();
+
+        assert_eq!(log.shifts().len(), 5);
+        assert!(log.shifts()[1].starts_before_midnight());
+        assert_eq!(log.shifts()[1].date(), Date::new(1518, 11, 2).unwrap());
+        assert_eq!(log.minutes_asleep(GuardId(10)), 50);
+        assert_eq!(log.sleepiest_guard(), Some(GuardId(10)));
+        assert_eq!(log.sleepiest_minutes(GuardId(10), 2), vec![(24, 2), (5, 1)]);
+        assert_eq!(log.most_frequent_minute(), Some((GuardId(99), 45, 3)));
+    }
+
+    #[test]
+    fn nobody_slept() {
+        let lines = [
+            "[1518-11-01 00:00] Guard #10 begins shift",
+            "[1518-11-01 23:58] Guard #99 begins shift",
+        ];
+        let log = GuardLog::parse(lines).unwrap();
+        assert_eq!(log.shifts().len(), 2);
+        assert_eq!(log.sleepiest_minutes(GuardId(10), 1), vec![]);
+        assert_eq!(log.most_frequent_minute(), None);
+    }
+
+    #[test]
+    fn invalid_transition() {
+        let lines = [
+            "[1518-11-01 00:00] Guard #10 begins shift",
+            "[1518-11-01 00:05] falls asleep",
+            "[1518-11-01 00:07] falls asleep",
+        ];
+        let err = GuardLog::parse(lines).unwrap_err();
+        assert_eq!(err, LogError {
+            line: 3,
+            kind: LogErrorKind::AlreadyAsleep(GuardId(10)),
+        });
+        assert_eq!(err.to_string(), "line 3: guard #10 is already asleep");
+    }
+
+    #[test]
+    fn invalid_nap() {
+        let overnight = [
+            "[1518-11-01 00:00] Guard #10 begins shift",
+            "[1518-11-01 00:50] falls asleep",
+            "[1518-11-02 00:10] wakes up",
+        ];
+        assert_eq!(GuardLog::parse(overnight).unwrap_err(), LogError {
+            line: 3,
+            kind: LogErrorKind::InvalidNap(GuardId(10)),
+        });
+        let instant = [
+            "[1518-11-01 00:00] Guard #10 begins shift",
+            "[1518-11-01 00:20] falls asleep",
+            "[1518-11-01 00:20] wakes up",
+        ];
+        assert_eq!(GuardLog::parse(instant).unwrap_err().kind, LogErrorKind::InvalidNap(GuardId(10)));
+    }
+
+    #[test]
+    fn malformed() {
+        let err = GuardLog::parse(["", "[1518-11-01 00:00] Guard #x begins shift"]).unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "past the end of the hour")]
+    fn nap_past_the_hour() {
+        let mut asleep = SleepMinutes::default();
+        asleep.add_nap(50, 70);
+    }
+}
@@ -1,36 +1,21 @@
-use std::io::{BufRead, BufReader, Lines};
-use std::fs::File;
-use std::time::Instant;
-
-#[inline]
-pub fn time<F, Out>(label: &str, f: F) -> Out
-where
-    F: FnOnce() -> Out,
-{
-    let before = Instant::now();
-    let res = f();
-    println!("{}: {:?}", label, before.elapsed());
-    res
-}
+use aoc_common::input::Normalize;
+use std::path::Path;
 
 pub struct DataReader {
-    lines: Lines<BufReader<File>>,
+    lines: std::vec::IntoIter<String>,
 }
 
 impl DataReader {
     pub fn open(day: usize) -> Self {
-        let f = match File::open(format!("input/{}.txt", day)) {
-            Ok(f) => f,
-            Err(_) => panic!("Input for day {} is missing.", day),
-        };
-        let f = BufReader::new(f);
-        DataReader {
-            lines: f.lines(),
-        }
+        DataReader::open_with(day, &Normalize::new())
     }
-    pub fn text_io(self) -> TextIOLines {
-        TextIOLines {
-            lines: self.lines,
+    pub fn open_with(day: usize, options: &Normalize) -> Self {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join(format!("input/{}.txt", day));
+        let text = aoc_common::input::read(&path, day as u32, options);
+        let lines: Vec<String> = text.lines().map(String::from).collect();
+        DataReader {
+            lines: lines.into_iter(),
         }
     }
 }
@@ -38,18 +23,12 @@ impl DataReader {
 impl Iterator for DataReader {
     type Item = String;
     fn next(&mut self) -> Option<String> {
-        self.lines.next().map(|res| res.expect("IO error while reading string."))
+        self.lines.next()
     }
 }
 
-pub type TextIOLine = std::vec::IntoIter<u8>;
-pub struct TextIOLines {
-    lines: Lines<BufReader<File>>,
-}
-impl Iterator for TextIOLines {
-    type Item = TextIOLine;
-    fn next(&mut self) -> Option<TextIOLine> {
-        self.lines.next().map(|res| res.expect("IO error while reading string."))
-            .map(|string| string.into_bytes().into_iter())
-    }
-}
+
+pub mod guard_log;
+pub mod license;
+pub mod timestamp;
+pub mod tree;
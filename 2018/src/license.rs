@@ -0,0 +1,356 @@
+//! The license file format of day 8.
+//!
+//! Each node is written as a header with the number of children and the
+//! number of metadata entries, followed by the children and then the
+//! metadata entries.
+
+use crate::tree::{NodeId, Tree};
+use rand::Rng;
+use std::fmt;
+
+/// A license tree. Every node holds its metadata entries.
+pub type LicenseTree = Tree<Vec<u32>>;
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct DecodeError {
+    /// The position in the input at which the error was found.
+    pub offset: usize,
+    pub kind: DecodeErrorKind,
+}
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub enum DecodeErrorKind {
+    /// The input ended in the middle of a node.
+    Truncated,
+    /// The input continues after the root node ended.
+    TrailingData,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            DecodeErrorKind::Truncated => {
+                write!(f, "input ends in the middle of the node at offset {}", self.offset)
+            },
+            DecodeErrorKind::TrailingData => {
+                write!(f, "unexpected data after the root node at offset {}", self.offset)
+            },
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Decode a tree from the flat number stream.
+pub fn decode(nums: &[u32]) -> Result<LicenseTree, DecodeError> {
+    struct Frame {
+        node: NodeId,
+        /// Where the header of this node starts.
+        offset: usize,
+        children_left: u32,
+        metadata_len: usize,
+    }
+
+    let truncated = |offset| DecodeError {
+        offset,
+        kind: DecodeErrorKind::Truncated,
+    };
+    let header = |pos: usize| match nums.get(pos..pos + 2) {
+        Some(&[children, metadata]) => Ok((children, metadata as usize)),
+        _ => Err(truncated(pos)),
+    };
+
+    let (children_left, metadata_len) = header(0)?;
+    let mut tree = Tree::new(Vec::new());
+    let mut stack = vec![Frame {
+        node: tree.root(),
+        offset: 0,
+        children_left,
+        metadata_len,
+    }];
+    let mut pos = 2;
+
+    while let Some(frame) = stack.last_mut() {
+        if frame.children_left > 0 {
+            frame.children_left -= 1;
+            let node = tree.push_child(frame.node, Vec::new());
+            let (children_left, metadata_len) = header(pos)?;
+            stack.push(Frame {
+                node,
+                offset: pos,
+                children_left,
+                metadata_len,
+            });
+            pos += 2;
+        } else {
+            let metadata = nums.get(pos..)
+                .and_then(|rest| rest.get(..frame.metadata_len))
+                .ok_or_else(|| truncated(frame.offset))?;
+            tree[frame.node].extend_from_slice(metadata);
+            pos += frame.metadata_len;
+            stack.pop();
+        }
+    }
+
+    if pos != nums.len() {
+        return Err(DecodeError {
+            offset: pos,
+            kind: DecodeErrorKind::TrailingData,
+        });
+    }
+    Ok(tree)
+}
+
+/// Encode a tree as the flat number stream that `decode` reads.
+pub fn encode(tree: &LicenseTree) -> Vec<u32> {
+    let mut nums = Vec::new();
+    // The flag says whether the children of the node have been written.
+    let mut stack = vec![(tree.root(), false)];
+    while let Some((node, children_done)) = stack.pop() {
+        if children_done {
+            nums.extend_from_slice(&tree[node]);
+        } else {
+            let children = tree.children(node);
+            nums.push(children.len() as u32);
+            nums.push(tree[node].len() as u32);
+            stack.push((node, true));
+            stack.extend(children.iter().rev().map(|&child| (child, false)));
+        }
+    }
+    nums
+}
+
+/// Settings for generating random license trees.
+#[derive(Clone,Debug)]
+pub struct Generator {
+    /// Nodes at this depth have no children. The root has depth zero.
+    pub max_depth: usize,
+    /// Every node above `max_depth` gets between `min_children` and
+    /// `max_children` children, as long as `max_nodes` allows it.
+    pub min_children: u32,
+    pub max_children: u32,
+    /// Every node gets between one and `max_metadata` metadata entries.
+    pub max_metadata: u32,
+    /// Metadata entries are between one and `max_value`.
+    pub max_value: u32,
+    /// The size limit of the tree.
+    pub max_nodes: usize,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Generator {
+            max_depth: 8,
+            min_children: 0,
+            max_children: 4,
+            max_metadata: 4,
+            max_value: 10,
+            max_nodes: 10_000,
+        }
+    }
+}
+
+/// Settings of a [`Generator`] that can't produce any tree.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub enum GeneratorError {
+    /// Every node needs at least one metadata entry.
+    NoMetadata,
+    /// Metadata entries are at least one, so `max_value` can't be zero.
+    NoValues,
+    /// `min_children` is larger than `max_children`.
+    ChildRange { min: u32, max: u32 },
+}
+
+impl fmt::Display for GeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GeneratorError::NoMetadata => write!(f, "max_metadata must be at least one"),
+            GeneratorError::NoValues => write!(f, "max_value must be at least one"),
+            GeneratorError::ChildRange { min, max } => {
+                write!(f, "min_children {} is larger than max_children {}", min, max)
+            },
+        }
+    }
+}
+
+impl std::error::Error for GeneratorError {}
+
+impl Generator {
+    /// Generate a random tree. The tree is built depth first and every
+    /// level on the way down adds at most `max_children` nodes, so with
+    /// `min_children` at least one, the tree is at least
+    /// `min(max_depth, (max_nodes - 1) / max_children)` deep.
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> Result<LicenseTree, GeneratorError> {
+        if self.max_metadata == 0 {
+            return Err(GeneratorError::NoMetadata);
+        }
+        if self.max_value == 0 {
+            return Err(GeneratorError::NoValues);
+        }
+        if self.min_children > self.max_children {
+            return Err(GeneratorError::ChildRange {
+                min: self.min_children,
+                max: self.max_children,
+            });
+        }
+        let mut tree = Tree::new(self.metadata(rng));
+        let mut stack = vec![(tree.root(), 0)];
+        while let Some((node, depth)) = stack.pop() {
+            if depth == self.max_depth {
+                continue;
+            }
+            let children = rng.gen_range(self.min_children..=self.max_children);
+            for _ in 0..children {
+                if tree.len() >= self.max_nodes {
+                    break;
+                }
+                let child = tree.push_child(node, self.metadata(rng));
+                stack.push((child, depth + 1));
+            }
+        }
+        Ok(tree)
+    }
+
+    fn metadata<R: Rng>(&self, rng: &mut R) -> Vec<u32> {
+        let len = rng.gen_range(1..=self.max_metadata);
+        (0..len).map(|_| rng.gen_range(1..=self.max_value)).collect()
+    }
+}
+
+/// The sum of every metadata entry in the tree.
+pub fn metadata_sum(tree: &LicenseTree) -> u32 {
+    tree.fold(|metadata, children: Vec<u32>| {
+        metadata.iter().sum::<u32>() + children.iter().sum::<u32>()
+    })
+}
+
+/// The value of the root node. A node without children is worth the sum of
+/// its metadata, and any other node is worth the sum of the children its
+/// metadata entries refer to, counting from one.
+pub fn reference_sum(tree: &LicenseTree) -> u32 {
+    tree.fold(|metadata, children: Vec<u32>| {
+        if children.is_empty() {
+            metadata.iter().sum()
+        } else {
+            metadata.iter()
+                .filter_map(|&meta| children.get((meta as usize).wrapping_sub(1)))
+                .sum()
+        }
+    })
+}
+
+/// The nodes whose metadata counts towards `reference_sum`, along with the
+/// nodes referring to them.
+pub fn referenced_nodes(tree: &LicenseTree) -> Vec<NodeId> {
+    let mut seen = vec![false; tree.len()];
+    let mut stack = vec![tree.root()];
+    seen[tree.root().index()] = true;
+    let mut nodes = Vec::new();
+    while let Some(node) = stack.pop() {
+        nodes.push(node);
+        let children = tree.children(node);
+        for &meta in &tree[node] {
+            if let Some(&child) = children.get((meta as usize).wrapping_sub(1)) {
+                if !seen[child.index()] {
+                    seen[child.index()] = true;
+                    stack.push(child);
+                }
+            }
+        }
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    const EXAMPLE: [u32; 16] = [2, 3, 0, 3, 10, 11, 12, 1, 1, 0, 1, 99, 2, 1, 1, 2];
+
+    #[test]
+    fn example() {
+        let tree = decode(&EXAMPLE).unwrap();
+        assert_eq!(tree.len(), 4);
+        assert_eq!(metadata_sum(&tree), 138);
+        assert_eq!(reference_sum(&tree), 66);
+        assert_eq!(referenced_nodes(&tree).len(), 3);
+
+        let bfs: Vec<&[u32]> = tree.bfs().map(|id| &tree[id][..]).collect();
+        assert_eq!(bfs, [&[1, 1, 2][..], &[10, 11, 12], &[2], &[99]]);
+        let dfs: Vec<&[u32]> = tree.dfs().map(|id| &tree[id][..]).collect();
+        assert_eq!(dfs, [&[1, 1, 2][..], &[10, 11, 12], &[2], &[99]]);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(decode(&EXAMPLE[..15]), Err(DecodeError {
+            offset: 0,
+            kind: DecodeErrorKind::Truncated,
+        }));
+        assert_eq!(decode(&EXAMPLE[..8]), Err(DecodeError {
+            offset: 7,
+            kind: DecodeErrorKind::Truncated,
+        }));
+        assert_eq!(decode(&[0, 1, 5, 7]), Err(DecodeError {
+            offset: 3,
+            kind: DecodeErrorKind::TrailingData,
+        }));
+        assert!(decode(&[]).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        assert_eq!(encode(&decode(&EXAMPLE).unwrap()), EXAMPLE);
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(8);
+        for _ in 0..20 {
+            let tree = Generator::default().generate(&mut rng).unwrap();
+            assert_eq!(decode(&encode(&tree)), Ok(tree));
+        }
+    }
+
+    #[test]
+    fn generate_deep() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(8);
+        let tree = Generator {
+            max_depth: 50_000,
+            min_children: 1,
+            max_children: 3,
+            max_nodes: 100_000,
+            ..Generator::default()
+        }.generate(&mut rng).unwrap();
+        assert_eq!(tree.len(), 100_000);
+        let depth = tree.fold(|_, children: Vec<usize>| {
+            children.into_iter().max().map_or(0, |depth| depth + 1)
+        });
+        assert!((33_333..=50_000).contains(&depth), "depth {}", depth);
+    }
+
+    #[test]
+    fn invalid_generator() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(8);
+        let mut generate = |generator: Generator| generator.generate(&mut rng).map(|tree| tree.len());
+        assert_eq!(generate(Generator { max_metadata: 0, ..Generator::default() }),
+            Err(GeneratorError::NoMetadata));
+        assert_eq!(generate(Generator { max_value: 0, ..Generator::default() }),
+            Err(GeneratorError::NoValues));
+        assert_eq!(generate(Generator { min_children: 3, max_children: 2, ..Generator::default() }),
+            Err(GeneratorError::ChildRange { min: 3, max: 2 }));
+    }
+
+    #[test]
+    fn deep_tree() {
+        let depth = 1_000_000;
+        let mut nums = Vec::new();
+        for _ in 0..depth {
+            nums.extend_from_slice(&[1, 1]);
+        }
+        nums.extend_from_slice(&[0, 1]);
+        nums.extend(std::iter::repeat_n(1, depth + 1));
+
+        let tree = decode(&nums).unwrap();
+        assert_eq!(metadata_sum(&tree), depth as u32 + 1);
+        assert_eq!(reference_sum(&tree), 1);
+    }
+}
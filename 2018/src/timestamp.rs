@@ -0,0 +1,258 @@
+//! Minute-resolution timestamps such as `[1518-11-01 23:58]`.
+//!
+//! Dates use the proleptic Gregorian calendar, so day and month rollover work
+//! for any year, including the ones the elves like to use.
+
+use std::fmt;
+use std::ops::{Add, Sub};
+use std::str::FromStr;
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// A calendar date.
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash,PartialOrd,Ord)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// Returns `None` if the day does not exist.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Date> {
+        if month == 0 || month > 12 || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+    pub fn year(self) -> i32 {
+        self.year
+    }
+    pub fn month(self) -> u32 {
+        self.month
+    }
+    pub fn day(self) -> u32 {
+        self.day
+    }
+    pub fn next_day(self) -> Date {
+        Date::from_days(self.days() + 1)
+    }
+    pub fn prev_day(self) -> Date {
+        Date::from_days(self.days() - 1)
+    }
+    /// The timestamp at midnight at the start of this day.
+    pub fn midnight(self) -> Timestamp {
+        Timestamp {
+            minutes: self.days() * MINUTES_PER_DAY,
+        }
+    }
+
+    /// Number of days since 1970-01-01.
+    fn days(self) -> i64 {
+        // See http://howardhinnant.github.io/date_algorithms.html
+        let y = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let m = i64::from(self.month);
+        let mp = if m > 2 { m - 3 } else { m + 9 };
+        let doy = (153 * mp + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+    fn from_days(days: i64) -> Date {
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Date { year, month, day }
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// A point in time with minute resolution.
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash,PartialOrd,Ord)]
+pub struct Timestamp {
+    /// Minutes since 1970-01-01 00:00.
+    minutes: i64,
+}
+
+impl Timestamp {
+    /// Returns `None` if the date or time does not exist.
+    pub fn new(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> Option<Timestamp> {
+        if hour >= 24 || minute >= 60 {
+            return None;
+        }
+        let date = Date::new(year, month, day)?;
+        Some(date.midnight() + Duration::minutes(i64::from(hour * 60 + minute)))
+    }
+    pub fn date(self) -> Date {
+        Date::from_days(self.minutes.div_euclid(MINUTES_PER_DAY))
+    }
+    pub fn hour(self) -> u32 {
+        (self.minutes.rem_euclid(MINUTES_PER_DAY) / 60) as u32
+    }
+    pub fn minute(self) -> u32 {
+        (self.minutes.rem_euclid(60)) as u32
+    }
+    /// The date of the midnight closest to this timestamp. Anything from noon
+    /// onwards belongs to the next day, so a shift starting at 23:58 is
+    /// grouped with the following day.
+    pub fn nearest_midnight(self) -> Date {
+        (self + Duration::hours(12)).date()
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {:02}:{:02}", self.date(), self.hour(), self.minute())
+    }
+}
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct ParseTimestampError;
+
+impl fmt::Display for ParseTimestampError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected a timestamp of the form [YYYY-MM-DD HH:MM]")
+    }
+}
+
+impl std::error::Error for ParseTimestampError {}
+
+impl FromStr for Timestamp {
+    type Err = ParseTimestampError;
+
+    /// Parses `YYYY-MM-DD HH:MM`, optionally surrounded by brackets.
+    fn from_str(s: &str) -> Result<Timestamp, ParseTimestampError> {
+        let s = s.trim();
+        let s = match s.strip_prefix('[') {
+            Some(s) => s.strip_suffix(']').ok_or(ParseTimestampError)?,
+            None => s,
+        };
+        let parse = || {
+            let (date, time) = s.split_once(' ')?;
+            let mut date = date.splitn(3, '-');
+            let (hour, minute) = time.split_once(':')?;
+            Timestamp::new(
+                date.next()?.parse().ok()?,
+                date.next()?.parse().ok()?,
+                date.next()?.parse().ok()?,
+                hour.parse().ok()?,
+                minute.parse().ok()?,
+            )
+        };
+        parse().ok_or(ParseTimestampError)
+    }
+}
+
+/// A signed span of time with minute resolution.
+#[derive(Copy,Clone,Debug,Default,Eq,PartialEq,Hash,PartialOrd,Ord)]
+pub struct Duration(i64);
+
+impl Duration {
+    pub fn minutes(minutes: i64) -> Duration {
+        Duration(minutes)
+    }
+    pub fn hours(hours: i64) -> Duration {
+        Duration(hours * 60)
+    }
+    pub fn days(days: i64) -> Duration {
+        Duration(days * MINUTES_PER_DAY)
+    }
+    pub fn as_minutes(self) -> i64 {
+        self.0
+    }
+}
+
+impl Add for Duration {
+    type Output = Duration;
+    fn add(self, other: Duration) -> Duration {
+        Duration(self.0 + other.0)
+    }
+}
+
+impl Sub for Duration {
+    type Output = Duration;
+    fn sub(self, other: Duration) -> Duration {
+        Duration(self.0 - other.0)
+    }
+}
+
+impl Add<Duration> for Timestamp {
+    type Output = Timestamp;
+    fn add(self, d: Duration) -> Timestamp {
+        Timestamp {
+            minutes: self.minutes + d.0,
+        }
+    }
+}
+
+impl Sub<Duration> for Timestamp {
+    type Output = Timestamp;
+    fn sub(self, d: Duration) -> Timestamp {
+        Timestamp {
+            minutes: self.minutes - d.0,
+        }
+    }
+}
+
+impl Sub for Timestamp {
+    type Output = Duration;
+    fn sub(self, other: Timestamp) -> Duration {
+        Duration(self.minutes - other.minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        let t: Timestamp = "[1518-11-01 23:58]".parse().unwrap();
+        assert_eq!((t.hour(), t.minute()), (23, 58));
+        assert_eq!(t.to_string(), "1518-11-01 23:58");
+        assert_eq!("1518-02-29 00:00".parse::<Timestamp>(), Err(ParseTimestampError));
+        assert_eq!("[1518-11-01 24:00]".parse::<Timestamp>(), Err(ParseTimestampError));
+    }
+
+    #[test]
+    fn rollover() {
+        let t: Timestamp = "[1518-12-31 23:58]".parse().unwrap();
+        assert_eq!((t + Duration::minutes(3)).to_string(), "1519-01-01 00:01");
+        assert_eq!(t.nearest_midnight(), Date::new(1519, 1, 1).unwrap());
+        assert_eq!(Date::new(1600, 2, 28).unwrap().next_day().day(), 29);
+        assert_eq!(Date::new(1700, 3, 1).unwrap().prev_day().day(), 28);
+    }
+
+    #[test]
+    fn durations() {
+        let a: Timestamp = "[1518-11-01 23:58]".parse().unwrap();
+        let b: Timestamp = "[1518-11-02 00:40]".parse().unwrap();
+        assert_eq!(b - a, Duration::minutes(42));
+        assert_eq!(a - Duration::days(1) + Duration::days(1), a);
+    }
+}
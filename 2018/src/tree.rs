@@ -0,0 +1,187 @@
+//! A rooted tree stored in a `Vec`.
+//!
+//! Nodes refer to their children by index, so no operation on the tree
+//! recurses. This keeps very deep trees from overflowing the stack, both
+//! while traversing them and when dropping them.
+
+use aoc_common::diagram::Diagram;
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash,PartialOrd,Ord)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Clone,Debug)]
+struct Node<T> {
+    value: T,
+    children: Vec<NodeId>,
+}
+
+#[derive(Clone,Debug)]
+pub struct Tree<T> {
+    nodes: Vec<Node<T>>,
+}
+
+impl<T> Tree<T> {
+    pub fn new(root: T) -> Self {
+        Tree {
+            nodes: vec![Node {
+                value: root,
+                children: Vec::new(),
+            }],
+        }
+    }
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+    /// The number of nodes in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+    /// A tree always has a root, so this is always false.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+    /// Add a new last child to `parent`.
+    pub fn push_child(&mut self, parent: NodeId, value: T) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Node {
+            value,
+            children: Vec::new(),
+        });
+        self.nodes[parent.0].children.push(id);
+        id
+    }
+    pub fn children(&self, node: NodeId) -> &[NodeId] {
+        &self.nodes[node.0].children
+    }
+
+    /// Depth-first traversal, visiting every node before its children.
+    pub fn dfs(&self) -> Dfs<'_, T> {
+        Dfs {
+            tree: self,
+            stack: vec![self.root()],
+        }
+    }
+    /// Breadth-first traversal, visiting the nodes level by level.
+    pub fn bfs(&self) -> Bfs<'_, T> {
+        let mut queue = VecDeque::new();
+        queue.push_back(self.root());
+        Bfs {
+            tree: self,
+            queue,
+        }
+    }
+    /// Every node, ordered such that each node comes after its children.
+    pub fn post_order(&self) -> Vec<NodeId> {
+        let mut order: Vec<NodeId> = self.dfs().collect();
+        // Reversing a pre-order traversal gives a post-order traversal of the
+        // mirrored tree, which is good enough for anything bottom-up.
+        order.reverse();
+        order
+    }
+
+    /// Compute a value for every node from its own value and the results of
+    /// its children, and return the result of the root.
+    pub fn fold<B, F>(&self, mut f: F) -> B
+    where
+        F: FnMut(&T, Vec<B>) -> B,
+    {
+        let mut results: Vec<Option<B>> = Vec::with_capacity(self.len());
+        results.resize_with(self.len(), || None);
+        for id in self.post_order() {
+            let children = self.nodes[id.0].children.iter()
+                .map(|child| results[child.0].take().unwrap())
+                .collect();
+            results[id.0] = Some(f(&self.nodes[id.0].value, children));
+        }
+        results[0].take().unwrap()
+    }
+
+    pub fn map<U, F>(self, mut f: F) -> Tree<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Tree {
+            nodes: self.nodes.into_iter()
+                .map(|node| Node {
+                    value: f(node.value),
+                    children: node.children,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Trees are equal if they have the same shape and values, no matter the
+/// order in which the nodes were added.
+impl<T: PartialEq> PartialEq for Tree<T> {
+    fn eq(&self, other: &Tree<T>) -> bool {
+        self.len() == other.len() && self.dfs().zip(other.dfs()).all(|(a, b)| {
+            self[a] == other[b] && self.children(a).len() == other.children(b).len()
+        })
+    }
+}
+
+impl<T: Eq> Eq for Tree<T> {}
+
+impl<T> Index<NodeId> for Tree<T> {
+    type Output = T;
+    fn index(&self, id: NodeId) -> &T {
+        &self.nodes[id.0].value
+    }
+}
+
+impl<T> IndexMut<NodeId> for Tree<T> {
+    fn index_mut(&mut self, id: NodeId) -> &mut T {
+        &mut self.nodes[id.0].value
+    }
+}
+
+/// Nodes are labelled with the debug format of their values.
+impl<T: fmt::Debug> Diagram for Tree<T> {
+    fn node_count(&self) -> usize {
+        self.len()
+    }
+    fn label(&self, node: usize) -> String {
+        format!("{:?}", self.nodes[node].value)
+    }
+    fn successors(&self, node: usize) -> Vec<usize> {
+        self.nodes[node].children.iter().map(|child| child.0).collect()
+    }
+}
+
+pub struct Dfs<'a, T> {
+    tree: &'a Tree<T>,
+    stack: Vec<NodeId>,
+}
+
+impl<'a, T> Iterator for Dfs<'a, T> {
+    type Item = NodeId;
+    fn next(&mut self) -> Option<NodeId> {
+        let id = self.stack.pop()?;
+        self.stack.extend(self.tree.children(id).iter().rev().copied());
+        Some(id)
+    }
+}
+
+pub struct Bfs<'a, T> {
+    tree: &'a Tree<T>,
+    queue: VecDeque<NodeId>,
+}
+
+impl<'a, T> Iterator for Bfs<'a, T> {
+    type Item = NodeId;
+    fn next(&mut self) -> Option<NodeId> {
+        let id = self.queue.pop_front()?;
+        self.queue.extend(self.tree.children(id).iter().copied());
+        Some(id)
+    }
+}
@@ -0,0 +1,33 @@
+//! The fuel requirements of day 1.
+
+/// The fuel needed to launch a module, ignoring the mass of the fuel.
+pub fn fuel(mass: u64) -> u64 {
+    (mass / 3).saturating_sub(2)
+}
+
+/// The fuel needed to launch a module, including the fuel for the fuel.
+pub fn total_fuel(mass: u64) -> u64 {
+    let mut total = 0;
+    let mut curr = fuel(mass);
+    while curr > 0 {
+        total += curr;
+        curr = fuel(curr);
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn examples() {
+        assert_eq!(fuel(12), 2);
+        assert_eq!(fuel(1969), 654);
+        assert_eq!(fuel(100756), 33583);
+        assert_eq!(fuel(2), 0);
+        assert_eq!(total_fuel(14), 2);
+        assert_eq!(total_fuel(1969), 966);
+        assert_eq!(total_fuel(100756), 50346);
+    }
+}
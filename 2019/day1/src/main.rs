@@ -0,0 +1,33 @@
+//! Reads the puzzle input from the file given as the first argument, or
+//! from `input.txt` next to this package's manifest.
+
+use aoc_common::input::Normalize;
+use aoc_common::tokens::parse_iter;
+use std::path::{Path, PathBuf};
+
+fn main() {
+    let path = match std::env::args_os().nth(1) {
+        Some(path) => PathBuf::from(path),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join("input.txt"),
+    };
+    let input = match aoc_common::input::load(&path, &Normalize::new()) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("Input for day 1: {}", err);
+            std::process::exit(1);
+        },
+    };
+
+    let masses: Vec<u64> = aoc_common::time("Read input", || {
+        parse_iter(input.lines())
+    });
+
+    let part1: u64 = aoc_common::time("Fuel", || {
+        masses.iter().map(|&mass| day1::fuel(mass)).sum()
+    });
+    let part2: u64 = aoc_common::time("Total fuel", || {
+        masses.iter().map(|&mass| day1::total_fuel(mass)).sum()
+    });
+    println!("Part one: {}", part1);
+    println!("Part two: {}", part2);
+}
@@ -0,0 +1,24 @@
+//! The gravity assist program of day 2.
+
+use intcode::{Error, Vm};
+
+/// Run a copy of the program with the given noun and verb, returning the
+/// value left at address 0.
+pub fn run_with(program: &[i64], noun: i64, verb: i64) -> Result<i64, Error> {
+    let mut vm = Vm::new(program.to_vec());
+    vm.write(1, noun);
+    vm.write(2, verb);
+    vm.run()?;
+    Ok(vm.read(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn examples() {
+        assert_eq!(run_with(&[1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50], 9, 10), Ok(3500));
+        assert_eq!(run_with(&[1, 1, 1, 4, 99, 5, 6, 0, 99], 1, 1), Ok(30));
+    }
+}
@@ -0,0 +1,51 @@
+//! Reads the puzzle input from the file given as the first argument, or
+//! from `input.txt` next to this package's manifest.
+
+use aoc_common::input::Normalize;
+use std::path::{Path, PathBuf};
+
+const TARGET: i64 = 19690720;
+
+fn main() {
+    let path = match std::env::args_os().nth(1) {
+        Some(path) => PathBuf::from(path),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join("input.txt"),
+    };
+    let input = match aoc_common::input::load(&path, &Normalize::new()) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("Input for day 2: {}", err);
+            std::process::exit(1);
+        },
+    };
+
+    let program: Vec<i64> = aoc_common::time("Read input", || {
+        match intcode::parse_program(&input) {
+            Ok(program) => program,
+            Err(err) => panic!("Invalid program: {}", err),
+        }
+    });
+
+    let part1 = aoc_common::time("Run program", || {
+        match day2::run_with(&program, 12, 2) {
+            Ok(value) => value,
+            Err(err) => panic!("Program failed: {}", err),
+        }
+    });
+    println!("Part one: {}", part1);
+
+    let part2 = aoc_common::time("Find noun and verb", || {
+        for noun in 0..100 {
+            for verb in 0..100 {
+                if day2::run_with(&program, noun, verb) == Ok(TARGET) {
+                    return Some(100 * noun + verb);
+                }
+            }
+        }
+        None
+    });
+    match part2 {
+        Some(answer) => println!("Part two: {}", answer),
+        None => println!("Part two: no noun and verb give {}", TARGET),
+    }
+}
@@ -0,0 +1,3 @@
+//! The crossing wires of day 3.
+
+pub mod polyline;
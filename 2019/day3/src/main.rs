@@ -0,0 +1,43 @@
+//! Reads the puzzle input from the file given as the first argument, or
+//! from `input.txt` next to this package's manifest.
+
+use aoc_common::input::Normalize;
+use day3::polyline::{self, Crossing, Wire};
+use std::path::{Path, PathBuf};
+
+fn main() {
+    let path = match std::env::args_os().nth(1) {
+        Some(path) => PathBuf::from(path),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join("input.txt"),
+    };
+    let input = match aoc_common::input::load(&path, &Normalize::new()) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("Input for day 3: {}", err);
+            std::process::exit(1);
+        },
+    };
+
+    let crossings = aoc_common::time("Find crossings", || {
+        let wires: Vec<Wire> = input.lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| match Wire::parse(line) {
+                Ok(wire) => wire,
+                Err(err) => panic!("Invalid wire: {}", err),
+            })
+            .collect();
+        if wires.len() != 2 {
+            panic!("Expected two wires, found {}.", wires.len());
+        }
+        polyline::crossings(&wires[0], &wires[1])
+    });
+
+    match crossings.iter().map(Crossing::distance).min() {
+        Some(distance) => println!("Part one: {}", distance),
+        None => println!("Part one: the wires never cross"),
+    }
+    match crossings.iter().map(Crossing::combined_steps).min() {
+        Some(steps) => println!("Part two: {}", steps),
+        None => println!("Part two: the wires never cross"),
+    }
+}
@@ -0,0 +1,292 @@
+//! Wires as chains of axis-aligned segments.
+//!
+//! Crossings are found with a sweep over the x axis: horizontal segments
+//! are active between their end points, and each vertical segment looks up
+//! the active segments within its y range. Segments lying on the same line
+//! are compared directly, and every point they share is a crossing.
+
+use aoc_common::point::{Dir4, Point};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct InvalidMove {
+    pub text: String,
+}
+
+impl fmt::Display for InvalidMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid move {:?}", self.text)
+    }
+}
+
+impl std::error::Error for InvalidMove {}
+
+/// A straight piece of wire. The end points are inclusive.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct Segment {
+    pub start: Point<i64>,
+    pub end: Point<i64>,
+    /// The length of the wire before this segment.
+    pub steps_before: u64,
+}
+
+impl Segment {
+    pub fn is_horizontal(&self) -> bool {
+        self.start.y == self.end.y
+    }
+    pub fn len(&self) -> u64 {
+        self.start.manhattan(self.end) as u64
+    }
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+    /// The wire length up to a point on this segment.
+    pub fn steps_to(&self, pos: Point<i64>) -> u64 {
+        self.steps_before + self.start.manhattan(pos) as u64
+    }
+    /// The coordinate that is constant along the segment, and the range of
+    /// the other coordinate.
+    fn line(&self) -> (i64, i64, i64) {
+        if self.is_horizontal() {
+            let (x1, x2) = min_max(self.start.x, self.end.x);
+            (self.start.y, x1, x2)
+        } else {
+            let (y1, y2) = min_max(self.start.y, self.end.y);
+            (self.start.x, y1, y2)
+        }
+    }
+}
+
+fn min_max(a: i64, b: i64) -> (i64, i64) {
+    (i64::min(a, b), i64::max(a, b))
+}
+
+/// A wire starting at the origin.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Wire {
+    pub segments: Vec<Segment>,
+}
+
+impl Wire {
+    /// Parse a path such as `R8,U5,L5,D3`.
+    pub fn parse(path: &str) -> Result<Wire, InvalidMove> {
+        let mut segments = Vec::new();
+        let mut pos = Point::new(0i64, 0);
+        let mut steps = 0;
+        for step in path.trim().split(',') {
+            let invalid = || InvalidMove { text: step.to_string() };
+            let mut chars = step.chars();
+            let dir = chars.next().and_then(Dir4::from_char).ok_or_else(invalid)?;
+            let len: i64 = chars.as_str().parse().map_err(|_| invalid())?;
+            if len < 0 {
+                return Err(invalid());
+            }
+            let end = pos + dir.delta() * len;
+            segments.push(Segment {
+                start: pos,
+                end,
+                steps_before: steps,
+            });
+            pos = end;
+            steps += len as u64;
+        }
+        Ok(Wire { segments })
+    }
+}
+
+/// A point where two wires cross, other than the origin.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct Crossing {
+    pub pos: Point<i64>,
+    /// The steps along each wire to the first time it reaches the point.
+    pub steps: (u64, u64),
+}
+
+impl Crossing {
+    /// The Manhattan distance to the origin.
+    pub fn distance(&self) -> u64 {
+        self.pos.manhattan(Point::new(0, 0)) as u64
+    }
+    pub fn combined_steps(&self) -> u64 {
+        self.steps.0 + self.steps.1
+    }
+}
+
+/// Every point where a horizontal segment of `horiz` meets a vertical
+/// segment of `vert`, as `(pos, horizontal segment, vertical segment)`.
+fn sweep<'a>(horiz: &'a Wire, vert: &'a Wire) -> Vec<(Point<i64>, &'a Segment, &'a Segment)> {
+    // Events at the same x are ordered as additions, queries and removals,
+    // so segments meeting at their end points are found.
+    const ADD: u8 = 0;
+    const QUERY: u8 = 1;
+    const REMOVE: u8 = 2;
+    let mut events = Vec::new();
+    for seg in horiz.segments.iter().filter(|s| s.is_horizontal() && !s.is_empty()) {
+        let (_, x1, x2) = seg.line();
+        events.push((x1, ADD, seg));
+        events.push((x2, REMOVE, seg));
+    }
+    for seg in vert.segments.iter().filter(|s| !s.is_horizontal()) {
+        events.push((seg.start.x, QUERY, seg));
+    }
+    events.sort_by_key(|&(x, kind, _)| (x, kind));
+
+    let mut active: BTreeMap<i64, Vec<&Segment>> = BTreeMap::new();
+    let mut found = Vec::new();
+    for (x, kind, seg) in events {
+        match kind {
+            ADD => active.entry(seg.start.y).or_default().push(seg),
+            REMOVE => {
+                let at_y = active.get_mut(&seg.start.y).unwrap();
+                let idx = at_y.iter().position(|s| std::ptr::eq(*s, seg)).unwrap();
+                at_y.swap_remove(idx);
+                if at_y.is_empty() {
+                    active.remove(&seg.start.y);
+                }
+            },
+            _ => {
+                let (_, y1, y2) = seg.line();
+                for (&y, segs) in active.range(y1..=y2) {
+                    for h in segs {
+                        found.push((Point::new(x, y), *h, seg));
+                    }
+                }
+            },
+        }
+    }
+    found
+}
+
+/// Every point shared by two segments on the same line.
+fn overlaps<'a>(a: &'a Wire, b: &'a Wire) -> Vec<(Point<i64>, &'a Segment, &'a Segment)> {
+    let mut lines: HashMap<(bool, i64), Vec<&Segment>> = HashMap::new();
+    for seg in b.segments.iter().filter(|s| !s.is_empty()) {
+        lines.entry((seg.is_horizontal(), seg.line().0)).or_default().push(seg);
+    }
+    let mut found = Vec::new();
+    for sa in a.segments.iter().filter(|s| !s.is_empty()) {
+        let (c, a1, a2) = sa.line();
+        for sb in lines.get(&(sa.is_horizontal(), c)).into_iter().flatten() {
+            let (_, b1, b2) = sb.line();
+            for t in i64::max(a1, b1)..=i64::min(a2, b2) {
+                let pos = if sa.is_horizontal() { Point::new(t, c) } else { Point::new(c, t) };
+                found.push((pos, sa, *sb));
+            }
+        }
+    }
+    found
+}
+
+/// Every crossing of the two wires, in reading order.
+pub fn crossings(a: &Wire, b: &Wire) -> Vec<Crossing> {
+    let mut best: HashMap<Point<i64>, (u64, u64)> = HashMap::new();
+    let mut record = |pos: Point<i64>, sa: &Segment, sb: &Segment| {
+        if pos == Point::new(0, 0) {
+            return;
+        }
+        let steps = (sa.steps_to(pos), sb.steps_to(pos));
+        let entry = best.entry(pos).or_insert(steps);
+        entry.0 = u64::min(entry.0, steps.0);
+        entry.1 = u64::min(entry.1, steps.1);
+    };
+    for (pos, sa, sb) in sweep(a, b) {
+        record(pos, sa, sb);
+    }
+    for (pos, sb, sa) in sweep(b, a) {
+        record(pos, sa, sb);
+    }
+    for (pos, sa, sb) in overlaps(a, b) {
+        record(pos, sa, sb);
+    }
+
+    let mut crossings: Vec<Crossing> = best.into_iter()
+        .map(|(pos, steps)| Crossing { pos, steps })
+        .collect();
+    crossings.sort_unstable_by_key(|c| c.pos);
+    crossings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(a: &str, b: &str) -> (Option<u64>, Option<u64>) {
+        let crossings = crossings(&Wire::parse(a).unwrap(), &Wire::parse(b).unwrap());
+        (crossings.iter().map(Crossing::distance).min(),
+         crossings.iter().map(Crossing::combined_steps).min())
+    }
+
+    #[test]
+    fn examples() {
+        assert_eq!(solve("R8,U5,L5,D3", "U7,R6,D4,L4"), (Some(6), Some(30)));
+        assert_eq!(solve("R75,D30,R83,U83,L12,D49,R71,U7,L72",
+                         "U62,R66,U55,R34,D71,R55,D58,R83"), (Some(159), Some(610)));
+        assert_eq!(solve("R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51",
+                         "U98,R91,D20,R16,D67,R40,U7,R15,U6,R7"), (Some(135), Some(410)));
+        assert_eq!(solve("R2", "U2"), (None, None));
+    }
+
+    #[test]
+    fn crossing_details() {
+        let a = Wire::parse("R8,U5,L5,D3").unwrap();
+        let b = Wire::parse("U7,R6,D4,L4").unwrap();
+        assert_eq!(crossings(&a, &b), vec![
+            Crossing { pos: Point::new(6, -5), steps: (15, 15) },
+            Crossing { pos: Point::new(3, -3), steps: (20, 20) },
+        ]);
+    }
+
+    #[test]
+    fn overlapping_and_revisiting() {
+        // The wires share the cells from (2, 0) to (4, 0), and the first wire
+        // passes (3, 0) twice.
+        let a = Wire::parse("R4,U1,L1,D2").unwrap();
+        let b = Wire::parse("D1,R2,U1,R2").unwrap();
+        let found: Vec<((i64, i64), (u64, u64))> = crossings(&a, &b).iter()
+            .map(|c| ((c.pos.x, c.pos.y), c.steps))
+            .collect();
+        assert_eq!(found, vec![((2, 0), (2, 4)), ((3, 0), (3, 5)), ((4, 0), (4, 6))]);
+    }
+
+    /// Trace the wire one cell at a time.
+    fn cells(path: &str) -> HashMap<Point<i64>, u64> {
+        let mut visited = HashMap::new();
+        for seg in Wire::parse(path).unwrap().segments {
+            let d = (seg.end - seg.start).map(i64::signum);
+            for i in 1..=seg.len() as i64 {
+                let pos = seg.start + d * i;
+                visited.entry(pos).or_insert(seg.steps_before + i as u64);
+            }
+        }
+        visited
+    }
+
+    #[test]
+    fn matches_cell_by_cell() {
+        let mut state = 12345u64;
+        let mut random_path = || {
+            (0..30).map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                let dir = ["R", "L", "U", "D"][(state >> 60) as usize % 4];
+                format!("{}{}", dir, (state >> 40) % 8)
+            }).collect::<Vec<_>>().join(",")
+        };
+        for _ in 0..50 {
+            let (a, b) = (random_path(), random_path());
+            let (cells_a, cells_b) = (cells(&a), cells(&b));
+            let mut expected: Vec<Crossing> = cells_a.iter()
+                .filter(|&(&pos, _)| pos != Point::new(0, 0))
+                .filter_map(|(&pos, &sa)| Some(Crossing { pos, steps: (sa, *cells_b.get(&pos)?) }))
+                .collect();
+            expected.sort_unstable_by_key(|c| c.pos);
+            assert_eq!(crossings(&Wire::parse(&a).unwrap(), &Wire::parse(&b).unwrap()), expected);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(Wire::parse("R2,X3"), Err(InvalidMove { text: "X3".to_string() }));
+        assert_eq!(Wire::parse("R"), Err(InvalidMove { text: "R".to_string() }));
+    }
+}
@@ -0,0 +1,163 @@
+//! An interactive debugger for Intcode programs.
+//!
+//! Usage: `cargo run -p intcode --bin intcode-debug [program]`. Without an
+//! argument the day 2 input is loaded. Type `help` for the commands.
+
+use aoc_common::input::Normalize;
+use intcode::debug::{Debugger, Stop, TraceEntry};
+use intcode::disasm::disassemble_from;
+use intcode::Vm;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+const HELP: &str = "\
+commands:
+  s [n]          step n instructions (default 1)
+  c              continue until a breakpoint, watchpoint, input, output or halt
+  b <addr>       set a breakpoint        db <addr>  delete a breakpoint
+  w <addr>       set a watchpoint        dw <addr>  delete a watchpoint
+  i <values..>   queue input values
+  d [addr] [n]   disassemble n lines (default: 10 from the instruction pointer, at most 1000)
+  p <addr> [n]   print n memory values (default 1, at most 1000)
+  set <addr> <v> write a value to memory
+  r              show registers, breakpoints and watchpoints
+  t              toggle tracing of executed instructions
+  q              quit";
+
+/// The most lines `d` and `p` print at once.
+const MAX_LINES: usize = 1000;
+
+fn main() {
+    let path = match std::env::args_os().nth(1) {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../day2/input.txt"),
+    };
+    let input = match std::fs::read(&path).map(|bytes| Normalize::new().apply(&bytes)) {
+        Ok(Ok(input)) => input,
+        Ok(Err(err)) => panic!("{} has {}.", path.display(), err),
+        Err(err) => panic!("Cannot read {}: {}", path.display(), err),
+    };
+    let program = match intcode::parse_program(&input) {
+        Ok(program) => program,
+        Err(err) => panic!("Invalid program: {}", err),
+    };
+
+    println!("Loaded {} values from {}. Type help for the commands.", program.len(), path.display());
+    let mut dbg = Debugger::new(Vm::new(program));
+    let mut tracing = false;
+
+    let stdin = io::stdin();
+    loop {
+        print!("{:>5}> ", dbg.vm.ip());
+        io::stdout().flush().unwrap();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&cmd, args)) = words.split_first() else {
+            continue;
+        };
+        let args: Result<Vec<i64>, _> = args.iter().map(|arg| arg.parse::<i64>()).collect();
+        let args = match args {
+            Ok(args) => args,
+            Err(err) => {
+                println!("invalid argument: {}", err);
+                continue;
+            },
+        };
+        let addr = |i: usize| args.get(i).and_then(|&v| usize::try_from(v).ok());
+
+        match (cmd, args.len()) {
+            ("q", _) | ("quit", _) => break,
+            ("h", _) | ("help", _) => println!("{}", HELP),
+            ("s", 0 | 1) => {
+                let count = args.first().copied().unwrap_or(1);
+                for _ in 0..count {
+                    match dbg.step() {
+                        Ok(entry) => {
+                            println!("{}", entry);
+                            if entry.step != intcode::Step::Continue {
+                                break;
+                            }
+                        },
+                        Err(err) => {
+                            println!("error: {}", err);
+                            break;
+                        },
+                    }
+                }
+            },
+            ("c", 0) => {
+                let on_step = |entry: &TraceEntry| {
+                    if tracing {
+                        println!("{}", entry);
+                    }
+                };
+                match dbg.resume(on_step) {
+                    Ok(Stop::Breakpoint(addr)) => println!("breakpoint at {}", addr),
+                    Ok(Stop::Watchpoint(w)) => println!("watchpoint: [{}] {} -> {}", w.addr, w.old, w.new),
+                    Ok(Stop::NeedInput) => println!("waiting for input"),
+                    Ok(Stop::Output(value)) => println!("output {}", value),
+                    Ok(Stop::Halted) => println!("halted"),
+                    Err(err) => println!("error: {}", err),
+                }
+            },
+            ("b", 1) | ("db", 1) | ("w", 1) | ("dw", 1) => {
+                let Some(addr) = addr(0) else {
+                    println!("invalid address");
+                    continue;
+                };
+                let changed = match cmd {
+                    "b" => dbg.add_breakpoint(addr),
+                    "db" => dbg.remove_breakpoint(addr),
+                    "w" => dbg.add_watchpoint(addr),
+                    _ => dbg.remove_watchpoint(addr),
+                };
+                if !changed {
+                    println!("nothing to do");
+                }
+            },
+            ("i", _) => dbg.vm.extend_input(args.iter().copied()),
+            ("d", 0..=2) => {
+                let start = if args.is_empty() { Some(dbg.vm.ip()) } else { addr(0) };
+                let Some(start) = start else {
+                    println!("invalid address");
+                    continue;
+                };
+                let count = args.get(1).map_or(10, |&n| n.clamp(0, MAX_LINES as i64) as usize);
+                for line in disassemble_from(dbg.vm.memory(), start, count) {
+                    let marker = if line.addr == dbg.vm.ip() { '>' } else { ' ' };
+                    println!("{}{}", marker, line);
+                }
+            },
+            ("p", 1 | 2) => {
+                let Some(start) = addr(0) else {
+                    println!("invalid address");
+                    continue;
+                };
+                let count = args.get(1).map_or(1, |&n| n.clamp(0, MAX_LINES as i64) as usize);
+                for addr in start..start.saturating_add(count) {
+                    println!("[{}] = {}", addr, dbg.vm.read(addr));
+                }
+            },
+            ("set", 2) => match addr(0) {
+                Some(addr) if addr < Vm::MAX_MEMORY => dbg.vm.write(addr, args[1]),
+                Some(_) => println!("address is past the memory limit of {}", Vm::MAX_MEMORY),
+                None => println!("invalid address"),
+            },
+            ("r", 0) => {
+                println!("ip={} rb={} pending input={} halted={}",
+                         dbg.vm.ip(), dbg.vm.relative_base(),
+                         dbg.vm.pending_input(), dbg.vm.is_halted());
+                println!("breakpoints: {:?}", dbg.breakpoints().collect::<Vec<_>>());
+                println!("watchpoints: {:?}", dbg.watchpoints().collect::<Vec<_>>());
+            },
+            ("t", 0) => {
+                tracing = !tracing;
+                println!("tracing {}", if tracing { "on" } else { "off" });
+            },
+            _ => println!("unknown command, type help for the commands"),
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Stepping through programs with breakpoints, watchpoints and a trace of
+//! every executed instruction.
+
+use crate::disasm::Disassembly;
+use crate::vm::{Error, Step, Vm};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// A memory write made by an instruction.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct Write {
+    pub addr: usize,
+    pub old: i64,
+    pub new: i64,
+}
+
+/// An executed instruction.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct TraceEntry {
+    /// The instruction as it was before it ran.
+    pub instr: Disassembly,
+    pub relative_base: i64,
+    pub step: Step,
+    pub write: Option<Write>,
+}
+
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let instr = self.instr.to_string();
+        write!(f, "{:<40} rb={}", instr, self.relative_base)?;
+        if let Some(write) = self.write {
+            write!(f, "  [{}] {} -> {}", write.addr, write.old, write.new)?;
+        }
+        match self.step {
+            Step::Output(value) => write!(f, "  output {}", value),
+            Step::NeedInput => write!(f, "  waiting for input"),
+            Step::Continue | Step::Halted => Ok(()),
+        }
+    }
+}
+
+/// Why [`Debugger::resume`] stopped.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub enum Stop {
+    /// The instruction pointer reached a breakpoint. The instruction there
+    /// has not run yet.
+    Breakpoint(usize),
+    Watchpoint(Write),
+    NeedInput,
+    Output(i64),
+    Halted,
+}
+
+pub struct Debugger {
+    pub vm: Vm,
+    breakpoints: BTreeSet<usize>,
+    watchpoints: BTreeSet<usize>,
+}
+
+impl Debugger {
+    pub fn new(vm: Vm) -> Self {
+        Debugger {
+            vm,
+            breakpoints: BTreeSet::new(),
+            watchpoints: BTreeSet::new(),
+        }
+    }
+
+    /// Returns `false` if there already was a breakpoint at `addr`.
+    pub fn add_breakpoint(&mut self, addr: usize) -> bool {
+        self.breakpoints.insert(addr)
+    }
+    pub fn remove_breakpoint(&mut self, addr: usize) -> bool {
+        self.breakpoints.remove(&addr)
+    }
+    pub fn breakpoints(&self) -> impl Iterator<Item = usize> + '_ {
+        self.breakpoints.iter().copied()
+    }
+    /// Stop whenever an instruction writes to `addr`, even if the value
+    /// doesn't change.
+    pub fn add_watchpoint(&mut self, addr: usize) -> bool {
+        self.watchpoints.insert(addr)
+    }
+    pub fn remove_watchpoint(&mut self, addr: usize) -> bool {
+        self.watchpoints.remove(&addr)
+    }
+    pub fn watchpoints(&self) -> impl Iterator<Item = usize> + '_ {
+        self.watchpoints.iter().copied()
+    }
+
+    /// Execute a single instruction, ignoring breakpoints and watchpoints.
+    pub fn step(&mut self) -> Result<TraceEntry, Error> {
+        let instr = Disassembly::at(self.vm.memory(), self.vm.ip());
+        let relative_base = self.vm.relative_base();
+        let target = match self.vm.current_instruction() {
+            Ok(decoded) => match decoded.opcode.output_param() {
+                Some(i) => Some(self.vm.param_addr(&decoded, i)?),
+                None => None,
+            },
+            Err(_) => None,
+        };
+        let old = target.map(|addr| self.vm.read(addr));
+
+        let step = self.vm.step()?;
+        let write = match (target, old, step) {
+            (Some(addr), Some(old), Step::Continue) => Some(Write {
+                addr,
+                old,
+                new: self.vm.read(addr),
+            }),
+            _ => None,
+        };
+        Ok(TraceEntry {
+            instr,
+            relative_base,
+            step,
+            write,
+        })
+    }
+
+    /// Run until a breakpoint or watchpoint is hit, or the VM pauses. A
+    /// breakpoint at the current instruction is skipped, so resuming after a
+    /// breakpoint makes progress. Every executed instruction is passed to
+    /// `on_step`.
+    pub fn resume<F: FnMut(&TraceEntry)>(&mut self, mut on_step: F) -> Result<Stop, Error> {
+        let mut first = true;
+        loop {
+            let ip = self.vm.ip();
+            if !first && self.breakpoints.contains(&ip) {
+                return Ok(Stop::Breakpoint(ip));
+            }
+            first = false;
+
+            let entry = self.step()?;
+            on_step(&entry);
+            match entry.step {
+                Step::Continue => {},
+                Step::NeedInput => return Ok(Stop::NeedInput),
+                Step::Output(value) => return Ok(Stop::Output(value)),
+                Step::Halted => return Ok(Stop::Halted),
+            }
+            if let Some(write) = entry.write {
+                if self.watchpoints.contains(&write.addr) {
+                    return Ok(Stop::Watchpoint(write));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Counts down from 3 in address 13, outputting each value.
+    const COUNTDOWN: [i64; 14] = [4, 13, 1001, 13, -1, 13, 1005, 13, 0, 99, 0, 0, 0, 3];
+
+    #[test]
+    fn breakpoints() {
+        let mut dbg = Debugger::new(Vm::new(COUNTDOWN.to_vec()));
+        dbg.add_breakpoint(6);
+        assert_eq!(dbg.resume(|_| {}), Ok(Stop::Output(3)));
+        assert_eq!(dbg.resume(|_| {}), Ok(Stop::Breakpoint(6)));
+        assert_eq!(dbg.vm.read(13), 2);
+        assert_eq!(dbg.resume(|_| {}), Ok(Stop::Output(2)));
+        dbg.remove_breakpoint(6);
+        let mut trace = Vec::new();
+        assert_eq!(dbg.resume(|entry| trace.push(entry.to_string())), Ok(Stop::Output(1)));
+        assert_eq!(trace.len(), 3);
+        assert!(trace[0].starts_with("    2: add [13], -1, [13]"));
+        assert!(trace[0].ends_with("[13] 2 -> 1"));
+    }
+
+    #[test]
+    fn watchpoints() {
+        let mut dbg = Debugger::new(Vm::new(COUNTDOWN.to_vec()));
+        dbg.add_watchpoint(13);
+        assert_eq!(dbg.resume(|_| {}), Ok(Stop::Output(3)));
+        assert_eq!(dbg.resume(|_| {}), Ok(Stop::Watchpoint(Write {
+            addr: 13,
+            old: 3,
+            new: 2,
+        })));
+        assert_eq!(dbg.vm.ip(), 6);
+    }
+
+    #[test]
+    fn input() {
+        let mut dbg = Debugger::new(Vm::new(vec![3, 0, 99]));
+        let entry = dbg.step().unwrap();
+        assert_eq!((entry.step, entry.write), (Step::NeedInput, None));
+        dbg.vm.push_input(7);
+        let entry = dbg.step().unwrap();
+        assert_eq!(entry.write, Some(Write { addr: 0, old: 3, new: 7 }));
+        assert_eq!(dbg.resume(|_| {}), Ok(Stop::Halted));
+    }
+}
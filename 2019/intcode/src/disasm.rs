@@ -0,0 +1,110 @@
+//! Turning programs into readable listings.
+//!
+//! Position parameters are written as `[addr]`, relative ones as
+//! `[rb+offset]` and immediate ones as plain numbers. Values that don't
+//! decode as an instruction are listed as `data`.
+
+use crate::instruction::{Instruction, Mode};
+use std::fmt;
+
+/// A single line of a listing.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Disassembly {
+    pub addr: usize,
+    /// `None` if the value at `addr` is not a valid instruction.
+    pub instr: Option<Instruction>,
+    /// The values covered by this line, starting with the instruction.
+    pub values: Vec<i64>,
+}
+
+impl Disassembly {
+    /// Disassemble the instruction at `addr`. Memory past the end of the
+    /// slice reads as zero, like in the VM.
+    pub fn at(memory: &[i64], addr: usize) -> Disassembly {
+        let read = |addr: usize| memory.get(addr).copied().unwrap_or(0);
+        match Instruction::decode(read(addr)) {
+            Ok(instr) => Disassembly {
+                addr,
+                instr: Some(instr),
+                values: (addr..addr.saturating_add(instr.size())).map(read).collect(),
+            },
+            Err(_) => Disassembly {
+                addr,
+                instr: None,
+                values: vec![read(addr)],
+            },
+        }
+    }
+    /// The address of the line following this one.
+    pub fn next_addr(&self) -> usize {
+        self.addr + self.values.len()
+    }
+}
+
+impl fmt::Display for Disassembly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:>5}: ", self.addr)?;
+        let instr = match self.instr {
+            Some(instr) => instr,
+            None => return write!(f, "data {}", self.values[0]),
+        };
+        write!(f, "{}", instr.opcode.mnemonic())?;
+        for (i, &value) in self.values[1..].iter().enumerate() {
+            let sep = if i == 0 { " " } else { ", " };
+            match instr.modes[i] {
+                Mode::Position => write!(f, "{}[{}]", sep, value)?,
+                Mode::Immediate => write!(f, "{}{}", sep, value)?,
+                Mode::Relative => write!(f, "{}[rb{:+}]", sep, value)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Disassemble `count` lines starting at `addr`, stopping early at the end
+/// of the address space.
+pub fn disassemble_from(memory: &[i64], addr: usize, count: usize) -> Vec<Disassembly> {
+    let mut lines = Vec::new();
+    let mut addr = Some(addr);
+    while let Some(start) = addr.filter(|_| lines.len() < count) {
+        let line = Disassembly::at(memory, start);
+        addr = start.checked_add(line.values.len());
+        lines.push(line);
+    }
+    lines
+}
+
+/// Disassemble the whole memory from the start. Since data and code are not
+/// separated, data following the code may be listed as instructions.
+pub fn disassemble(memory: &[i64]) -> Vec<Disassembly> {
+    let mut lines = Vec::new();
+    let mut addr = 0;
+    while addr < memory.len() {
+        let line = Disassembly::at(memory, addr);
+        addr = line.next_addr();
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listing() {
+        let memory = [1002, 4, 3, 4, 33, 109, -2, 204, 1, 99, 42];
+        let listing: Vec<String> = disassemble(&memory).iter().map(|l| l.to_string()).collect();
+        assert_eq!(listing, vec![
+            "    0: mul [4], 3, [4]",
+            "    4: data 33",
+            "    5: arb -2",
+            "    7: out [rb+1]",
+            "    9: halt",
+            "   10: data 42",
+        ]);
+        assert_eq!(disassemble_from(&memory, 9, 3).iter().map(|l| l.addr).collect::<Vec<_>>(),
+            vec![9, 10, 11]);
+        assert_eq!(disassemble_from(&memory, usize::MAX - 1, 5).len(), 2);
+    }
+}
@@ -0,0 +1,168 @@
+//! Decoding instructions.
+//!
+//! An instruction is a single value: the last two digits are the opcode and
+//! the digits above those give the mode of each parameter, starting with
+//! the hundreds digit for the first one.
+
+use std::fmt;
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+pub enum Opcode {
+    Add,
+    Mul,
+    Input,
+    Output,
+    JumpIfTrue,
+    JumpIfFalse,
+    LessThan,
+    Equals,
+    AdjustBase,
+    Halt,
+}
+
+impl Opcode {
+    pub fn from_code(code: i64) -> Option<Opcode> {
+        Some(match code {
+            1 => Opcode::Add,
+            2 => Opcode::Mul,
+            3 => Opcode::Input,
+            4 => Opcode::Output,
+            5 => Opcode::JumpIfTrue,
+            6 => Opcode::JumpIfFalse,
+            7 => Opcode::LessThan,
+            8 => Opcode::Equals,
+            9 => Opcode::AdjustBase,
+            99 => Opcode::Halt,
+            _ => return None,
+        })
+    }
+    /// The number of parameters following the instruction.
+    pub fn params(self) -> usize {
+        match self {
+            Opcode::Add | Opcode::Mul | Opcode::LessThan | Opcode::Equals => 3,
+            Opcode::JumpIfTrue | Opcode::JumpIfFalse => 2,
+            Opcode::Input | Opcode::Output | Opcode::AdjustBase => 1,
+            Opcode::Halt => 0,
+        }
+    }
+    /// The index of the parameter that is written to, if any.
+    pub fn output_param(self) -> Option<usize> {
+        match self {
+            Opcode::Add | Opcode::Mul | Opcode::LessThan | Opcode::Equals => Some(2),
+            Opcode::Input => Some(0),
+            _ => None,
+        }
+    }
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::Add => "add",
+            Opcode::Mul => "mul",
+            Opcode::Input => "in",
+            Opcode::Output => "out",
+            Opcode::JumpIfTrue => "jnz",
+            Opcode::JumpIfFalse => "jz",
+            Opcode::LessThan => "lt",
+            Opcode::Equals => "eq",
+            Opcode::AdjustBase => "arb",
+            Opcode::Halt => "halt",
+        }
+    }
+}
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+pub enum Mode {
+    /// The parameter is an address.
+    Position,
+    /// The parameter is the value itself.
+    Immediate,
+    /// The parameter is an address relative to the relative base.
+    Relative,
+}
+
+impl Mode {
+    pub fn from_digit(digit: i64) -> Option<Mode> {
+        match digit {
+            0 => Some(Mode::Position),
+            1 => Some(Mode::Immediate),
+            2 => Some(Mode::Relative),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub enum DecodeError {
+    UnknownOpcode(i64),
+    InvalidMode(i64),
+    /// An output parameter in immediate mode.
+    ImmediateOutput,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::UnknownOpcode(code) => write!(f, "unknown opcode {}", code),
+            DecodeError::InvalidMode(mode) => write!(f, "invalid parameter mode {}", mode),
+            DecodeError::ImmediateOutput => write!(f, "output parameter in immediate mode"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    /// The modes of the parameters. Only the first `opcode.params()` are
+    /// meaningful.
+    pub modes: [Mode; 3],
+}
+
+impl Instruction {
+    pub fn decode(value: i64) -> Result<Instruction, DecodeError> {
+        if value < 0 {
+            return Err(DecodeError::UnknownOpcode(value));
+        }
+        let opcode = Opcode::from_code(value % 100).ok_or(DecodeError::UnknownOpcode(value % 100))?;
+        let mut modes = [Mode::Position; 3];
+        let mut digits = value / 100;
+        for (i, mode) in modes.iter_mut().enumerate().take(opcode.params()) {
+            *mode = Mode::from_digit(digits % 10).ok_or(DecodeError::InvalidMode(digits % 10))?;
+            if *mode == Mode::Immediate && opcode.output_param() == Some(i) {
+                return Err(DecodeError::ImmediateOutput);
+            }
+            digits /= 10;
+        }
+        // Modes for parameters that the opcode doesn't have.
+        while digits != 0 {
+            if digits % 10 != 0 {
+                return Err(DecodeError::InvalidMode(digits % 10));
+            }
+            digits /= 10;
+        }
+        Ok(Instruction { opcode, modes })
+    }
+    /// The number of values the instruction occupies.
+    pub fn size(&self) -> usize {
+        1 + self.opcode.params()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode() {
+        assert_eq!(Instruction::decode(1002), Ok(Instruction {
+            opcode: Opcode::Mul,
+            modes: [Mode::Position, Mode::Immediate, Mode::Position],
+        }));
+        assert_eq!(Instruction::decode(204).map(|i| i.modes[0]), Ok(Mode::Relative));
+        assert_eq!(Instruction::decode(99).map(|i| i.size()), Ok(1));
+        assert_eq!(Instruction::decode(13), Err(DecodeError::UnknownOpcode(13)));
+        assert_eq!(Instruction::decode(301), Err(DecodeError::InvalidMode(3)));
+        assert_eq!(Instruction::decode(10001), Err(DecodeError::ImmediateOutput));
+        assert_eq!(Instruction::decode(1099), Err(DecodeError::InvalidMode(1)));
+    }
+}
@@ -0,0 +1,20 @@
+//! The Intcode computer used throughout 2019.
+//!
+//! A [`Vm`] runs a program until it needs an input, produces an output or
+//! halts, so a day can feed it values and collect results at its own pace.
+
+use aoc_common::tokens::{try_parse_iter, TokenError};
+
+pub mod debug;
+pub mod disasm;
+pub mod instruction;
+pub mod network;
+pub mod vm;
+
+pub use instruction::{Instruction, Mode, Opcode};
+pub use vm::{Error, Exit, Step, Vm};
+
+/// Parse a comma separated program.
+pub fn parse_program(text: &str) -> Result<Vec<i64>, TokenError> {
+    try_parse_iter(text.trim().split(','))
+}
@@ -0,0 +1,313 @@
+//! Running many machines that talk to each other.
+//!
+//! The network runs in rounds. In each round every machine that hasn't
+//! halted runs until it waits for input, and afterwards the outputs of the
+//! round are delivered. Since delivery happens between rounds, running the
+//! machines one after another or on separate threads gives the same result.
+//!
+//! Outputs are delivered in one of two ways:
+//!
+//!  * With [`Network::pipes`], every output of a machine is sent to the
+//!    machine it is connected to, such as in the amplifier loop.
+//!  * With [`Network::packets`], machines send packets as three outputs
+//!    `dest, x, y`, and a machine with no queued input reads `-1`. Each
+//!    machine first reads its own address.
+
+use crate::vm::{Error, Exit, Vm};
+use std::fmt;
+
+/// How the machines of a round are run.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub enum Schedule {
+    /// One machine after another on the current thread.
+    RoundRobin,
+    /// Every machine on its own thread.
+    Threaded,
+}
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct Packet {
+    /// The machine that sent the packet, or `None` if it was injected.
+    pub from: Option<usize>,
+    pub dest: i64,
+    pub x: i64,
+    pub y: i64,
+}
+
+/// A machine of the network failed.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct NetworkError {
+    pub node: usize,
+    pub err: Error,
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "machine {}: {}", self.node, self.err)
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+/// Everything sent while running the network.
+#[derive(Clone,Debug,Default,Eq,PartialEq)]
+pub struct Traffic {
+    /// Every output of a piped network, as `(node, value)`.
+    pub values: Vec<(usize, i64)>,
+    /// Every complete packet, including those to addresses outside the
+    /// network.
+    pub packets: Vec<Packet>,
+    /// The number of rounds that were run.
+    pub rounds: usize,
+}
+
+impl Traffic {
+    /// The packets sent to an address outside the network.
+    pub fn packets_to(&self, dest: i64) -> impl Iterator<Item = &Packet> {
+        self.packets.iter().filter(move |packet| packet.dest == dest)
+    }
+}
+
+#[derive(Clone,Debug)]
+enum Wiring {
+    Pipes(Vec<Option<usize>>),
+    /// The outputs of each machine that don't form a whole packet yet.
+    Packets(Vec<Vec<i64>>),
+}
+
+pub struct Network {
+    vms: Vec<Vm>,
+    wiring: Wiring,
+}
+
+/// What a machine did in a round.
+struct NodeRound {
+    outputs: Vec<i64>,
+    active: bool,
+}
+
+/// Run a machine until it waits for input. With an `idle_input`, a machine
+/// without queued input reads that value once before it stops. A halted
+/// machine is never active, even if input is still queued for it.
+fn run_node(vm: &mut Vm, idle_input: Option<i64>) -> Result<NodeRound, Error> {
+    let mut round = NodeRound {
+        outputs: Vec::new(),
+        active: !vm.is_halted() && vm.pending_input() > 0,
+    };
+    if vm.is_halted() {
+        return Ok(round);
+    }
+    let mut polled = false;
+    loop {
+        match vm.run()? {
+            Exit::Output(value) => round.outputs.push(value),
+            Exit::Halted => break,
+            Exit::NeedInput => match idle_input {
+                Some(value) if !polled => {
+                    polled = true;
+                    vm.push_input(value);
+                },
+                _ => break,
+            },
+        }
+    }
+    round.active |= !round.outputs.is_empty();
+    Ok(round)
+}
+
+impl Network {
+    /// Machines whose outputs go nowhere until they are connected.
+    pub fn pipes(vms: Vec<Vm>) -> Self {
+        let n = vms.len();
+        Network {
+            vms,
+            wiring: Wiring::Pipes(vec![None; n]),
+        }
+    }
+
+    /// Machines that send packets to each other. Machine `i` gets `i` as
+    /// its first input.
+    pub fn packets(mut vms: Vec<Vm>) -> Self {
+        for (i, vm) in vms.iter_mut().enumerate() {
+            vm.push_input(i as i64);
+        }
+        let n = vms.len();
+        Network {
+            vms,
+            wiring: Wiring::Packets(vec![Vec::new(); n]),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.vms.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.vms.is_empty()
+    }
+    pub fn vm(&self, node: usize) -> &Vm {
+        &self.vms[node]
+    }
+    pub fn vm_mut(&mut self, node: usize) -> &mut Vm {
+        &mut self.vms[node]
+    }
+    pub fn is_halted(&self) -> bool {
+        self.vms.iter().all(Vm::is_halted)
+    }
+
+    /// Send the outputs of `from` to `to`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the network sends packets.
+    pub fn connect(&mut self, from: usize, to: usize) {
+        assert!(to < self.vms.len(), "No machine {}.", to);
+        match &mut self.wiring {
+            Wiring::Pipes(pipes) => pipes[from] = Some(to),
+            Wiring::Packets(_) => panic!("Machines of a packet network cannot be connected."),
+        }
+    }
+
+    /// Queue an input for a machine.
+    pub fn inject(&mut self, node: usize, value: i64) {
+        self.vms[node].push_input(value);
+    }
+
+    /// Deliver a packet from outside the network. Packets to addresses
+    /// outside the network are dropped.
+    pub fn send(&mut self, dest: i64, x: i64, y: i64) {
+        if let Some(vm) = usize::try_from(dest).ok().and_then(|dest| self.vms.get_mut(dest)) {
+            vm.extend_input([x, y]);
+        }
+    }
+
+    /// Run a single round, adding what was sent to `traffic`. Returns
+    /// `false` if the network was idle: no machine read a queued input or
+    /// produced an output.
+    pub fn round(&mut self, schedule: Schedule, traffic: &mut Traffic) -> Result<bool, NetworkError> {
+        let idle_input = match self.wiring {
+            Wiring::Pipes(_) => None,
+            Wiring::Packets(_) => Some(-1),
+        };
+        let results: Vec<Result<NodeRound, Error>> = match schedule {
+            Schedule::RoundRobin => self.vms.iter_mut()
+                .map(|vm| run_node(vm, idle_input))
+                .collect(),
+            Schedule::Threaded => std::thread::scope(|scope| {
+                let handles: Vec<_> = self.vms.iter_mut()
+                    .map(|vm| scope.spawn(move || run_node(vm, idle_input)))
+                    .collect();
+                handles.into_iter()
+                    .map(|handle| handle.join().expect("Machine thread panicked."))
+                    .collect()
+            }),
+        };
+
+        traffic.rounds += 1;
+        let mut active = false;
+        for (node, result) in results.into_iter().enumerate() {
+            let round = result.map_err(|err| NetworkError { node, err })?;
+            active |= round.active;
+            match &mut self.wiring {
+                Wiring::Pipes(pipes) => {
+                    for value in round.outputs {
+                        traffic.values.push((node, value));
+                        if let Some(to) = pipes[node] {
+                            self.vms[to].push_input(value);
+                        }
+                    }
+                },
+                Wiring::Packets(partial) => {
+                    let partial = &mut partial[node];
+                    partial.extend(round.outputs);
+                    let whole = partial.len() - partial.len() % 3;
+                    let values: Vec<i64> = partial.drain(..whole).collect();
+                    for chunk in values.chunks(3) {
+                        let packet = Packet {
+                            from: Some(node),
+                            dest: chunk[0],
+                            x: chunk[1],
+                            y: chunk[2],
+                        };
+                        traffic.packets.push(packet);
+                        self.send(packet.dest, packet.x, packet.y);
+                    }
+                },
+            }
+        }
+        Ok(active)
+    }
+
+    /// Run rounds until the network is idle or every machine has halted.
+    pub fn run(&mut self, schedule: Schedule) -> Result<Traffic, NetworkError> {
+        let mut traffic = Traffic::default();
+        while !self.is_halted() && self.round(schedule, &mut traffic)? {}
+        Ok(traffic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_program;
+
+    const AMPLIFIER: &str = "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,\
+                             27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5";
+
+    fn amplifier_loop(phases: [i64; 5], schedule: Schedule) -> i64 {
+        let program = parse_program(AMPLIFIER).unwrap();
+        let mut network = Network::pipes(vec![Vm::new(program); 5]);
+        for (i, phase) in phases.into_iter().enumerate() {
+            network.connect(i, (i + 1) % 5);
+            network.inject(i, phase);
+        }
+        network.inject(0, 0);
+        let traffic = network.run(schedule).unwrap();
+        assert!(network.is_halted());
+        traffic.values.iter().rev().find(|&&(node, _)| node == 4).unwrap().1
+    }
+
+    #[test]
+    fn amplifiers() {
+        assert_eq!(amplifier_loop([9, 8, 7, 6, 5], Schedule::RoundRobin), 139629729);
+        assert_eq!(amplifier_loop([9, 8, 7, 6, 5], Schedule::Threaded), 139629729);
+    }
+
+    // Reads its address, sends (255, address, 7) and then polls forever.
+    const SENDER: [i64; 13] = [3, 100, 104, 255, 4, 100, 104, 7, 3, 101, 1105, 1, 8];
+
+    #[test]
+    fn packets() {
+        for schedule in [Schedule::RoundRobin, Schedule::Threaded] {
+            let mut network = Network::packets(vec![Vm::new(SENDER.to_vec()); 3]);
+            let traffic = network.run(schedule).unwrap();
+            let sent: Vec<(i64, i64)> = traffic.packets_to(255).map(|p| (p.x, p.y)).collect();
+            assert_eq!(sent, vec![(0, 7), (1, 7), (2, 7)]);
+            assert_eq!(traffic.rounds, 2);
+
+            // The network wakes up again when a packet is delivered.
+            network.send(1, 4, 5);
+            let traffic = network.run(schedule).unwrap();
+            assert_eq!(network.vm(1).read(101), -1);
+            assert_eq!(traffic.rounds, 2);
+        }
+    }
+
+    #[test]
+    fn halted_with_input() {
+        // The first machine halts with its input still queued while the
+        // second one waits for an input that never comes.
+        let mut network = Network::pipes(vec![Vm::new(vec![99]), Vm::new(vec![3, 0, 99])]);
+        network.connect(0, 1);
+        network.inject(0, 5);
+        let traffic = network.run(Schedule::RoundRobin).unwrap();
+        assert!(!network.is_halted());
+        assert!(traffic.rounds <= 2);
+    }
+
+    #[test]
+    fn failing_node() {
+        let mut network = Network::pipes(vec![Vm::new(vec![99]), Vm::new(vec![42])]);
+        let err = network.run(Schedule::Threaded).unwrap_err();
+        assert_eq!(err.node, 1);
+    }
+}
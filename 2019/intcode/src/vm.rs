@@ -0,0 +1,356 @@
+//! Running programs.
+
+use crate::instruction::{DecodeError, Instruction, Mode, Opcode};
+use std::collections::VecDeque;
+use std::fmt;
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub enum Error {
+    /// The instruction at `ip` could not be decoded.
+    Decode {
+        ip: usize,
+        err: DecodeError,
+    },
+    /// The instruction at `ip` uses a negative address.
+    NegativeAddress {
+        ip: usize,
+        addr: i64,
+    },
+    /// A jump to a negative address.
+    InvalidJump {
+        ip: usize,
+        target: i64,
+    },
+    /// Arithmetic in the instruction at `ip` overflowed an `i64`, either in
+    /// its result or when computing a relative address or base.
+    Overflow {
+        ip: usize,
+    },
+    /// The instruction at `ip` writes at or past [`Vm::MAX_MEMORY`].
+    AddressTooLarge {
+        ip: usize,
+        addr: usize,
+    },
+    /// The machine has halted and cannot run further.
+    Halted,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Decode { ip, err } => write!(f, "at {}: {}", ip, err),
+            Error::NegativeAddress { ip, addr } => {
+                write!(f, "at {}: negative address {}", ip, addr)
+            },
+            Error::InvalidJump { ip, target } => {
+                write!(f, "at {}: jump to negative address {}", ip, target)
+            },
+            Error::Overflow { ip } => write!(f, "at {}: arithmetic overflow", ip),
+            Error::AddressTooLarge { ip, addr } => {
+                write!(f, "at {}: write to address {} past the memory limit", ip, addr)
+            },
+            Error::Halted => write!(f, "the machine has halted"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The result of executing a single instruction.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub enum Step {
+    /// An instruction without visible effects was executed.
+    Continue,
+    /// The next instruction reads an input, but none is queued. The
+    /// instruction pointer is left on the input instruction.
+    NeedInput,
+    Output(i64),
+    Halted,
+}
+
+/// Why [`Vm::run`] stopped.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub enum Exit {
+    NeedInput,
+    Output(i64),
+    Halted,
+}
+
+/// An Intcode machine. Memory grows when written past its end, up to
+/// [`Vm::MAX_MEMORY`] values, and reading past the end gives zero.
+#[derive(Clone,Debug)]
+pub struct Vm {
+    memory: Vec<i64>,
+    ip: usize,
+    relative_base: i64,
+    input: VecDeque<i64>,
+    halted: bool,
+}
+
+impl Vm {
+    /// The most values memory can grow to. Programs that write further fail
+    /// with [`Error::AddressTooLarge`] instead of allocating without bound.
+    pub const MAX_MEMORY: usize = 1 << 22;
+
+    pub fn new(program: Vec<i64>) -> Self {
+        Vm {
+            memory: program,
+            ip: 0,
+            relative_base: 0,
+            input: VecDeque::new(),
+            halted: false,
+        }
+    }
+
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+    pub fn relative_base(&self) -> i64 {
+        self.relative_base
+    }
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+    pub fn memory(&self) -> &[i64] {
+        &self.memory
+    }
+    pub fn read(&self, addr: usize) -> i64 {
+        self.memory.get(addr).copied().unwrap_or(0)
+    }
+    /// Write a value, growing memory to hold it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `addr` is at or past [`Vm::MAX_MEMORY`].
+    pub fn write(&mut self, addr: usize, value: i64) {
+        assert!(addr < Vm::MAX_MEMORY, "Address {} is past the memory limit.", addr);
+        if addr >= self.memory.len() {
+            self.memory.resize(addr + 1, 0);
+        }
+        self.memory[addr] = value;
+    }
+
+    /// Queue a value for the next input instruction.
+    pub fn push_input(&mut self, value: i64) {
+        self.input.push_back(value);
+    }
+    pub fn extend_input<I: IntoIterator<Item = i64>>(&mut self, values: I) {
+        self.input.extend(values);
+    }
+    /// The inputs that have been queued but not yet read.
+    pub fn pending_input(&self) -> usize {
+        self.input.len()
+    }
+
+    /// Decode the instruction at the instruction pointer.
+    pub fn current_instruction(&self) -> Result<Instruction, Error> {
+        Instruction::decode(self.read(self.ip)).map_err(|err| Error::Decode {
+            ip: self.ip,
+            err,
+        })
+    }
+
+    /// The address that parameter `i` of the current instruction refers to.
+    /// Immediate parameters refer to their own location.
+    pub fn param_addr(&self, instr: &Instruction, i: usize) -> Result<usize, Error> {
+        let loc = self.ip + 1 + i;
+        let addr = match instr.modes[i] {
+            Mode::Position => self.read(loc),
+            Mode::Immediate => return Ok(loc),
+            Mode::Relative => self.relative_base.checked_add(self.read(loc))
+                .ok_or(Error::Overflow { ip: self.ip })?,
+        };
+        usize::try_from(addr).map_err(|_| Error::NegativeAddress { ip: self.ip, addr })
+    }
+
+    fn param(&self, instr: &Instruction, i: usize) -> Result<i64, Error> {
+        Ok(self.read(self.param_addr(instr, i)?))
+    }
+
+    /// Write the result of the current instruction.
+    fn store(&mut self, addr: usize, value: i64) -> Result<(), Error> {
+        if addr >= Vm::MAX_MEMORY {
+            return Err(Error::AddressTooLarge { ip: self.ip, addr });
+        }
+        self.write(addr, value);
+        Ok(())
+    }
+
+    fn jump(&mut self, target: i64) -> Result<(), Error> {
+        self.ip = usize::try_from(target).map_err(|_| Error::InvalidJump {
+            ip: self.ip,
+            target,
+        })?;
+        Ok(())
+    }
+
+    /// Execute a single instruction.
+    pub fn step(&mut self) -> Result<Step, Error> {
+        if self.halted {
+            return Err(Error::Halted);
+        }
+        let instr = self.current_instruction()?;
+        let next = self.ip + instr.size();
+        match instr.opcode {
+            Opcode::Add | Opcode::Mul | Opcode::LessThan | Opcode::Equals => {
+                let a = self.param(&instr, 0)?;
+                let b = self.param(&instr, 1)?;
+                let out = self.param_addr(&instr, 2)?;
+                let value = match instr.opcode {
+                    Opcode::Add => a.checked_add(b),
+                    Opcode::Mul => a.checked_mul(b),
+                    Opcode::LessThan => Some((a < b) as i64),
+                    _ => Some((a == b) as i64),
+                };
+                let value = value.ok_or(Error::Overflow { ip: self.ip })?;
+                self.store(out, value)?;
+            },
+            Opcode::Input => {
+                let out = self.param_addr(&instr, 0)?;
+                match self.input.pop_front() {
+                    Some(value) => self.store(out, value)?,
+                    None => return Ok(Step::NeedInput),
+                }
+            },
+            Opcode::Output => {
+                let value = self.param(&instr, 0)?;
+                self.ip = next;
+                return Ok(Step::Output(value));
+            },
+            Opcode::JumpIfTrue | Opcode::JumpIfFalse => {
+                let cond = self.param(&instr, 0)? != 0;
+                if cond == (instr.opcode == Opcode::JumpIfTrue) {
+                    let target = self.param(&instr, 1)?;
+                    return self.jump(target).map(|()| Step::Continue);
+                }
+            },
+            Opcode::AdjustBase => {
+                let offset = self.param(&instr, 0)?;
+                self.relative_base = self.relative_base.checked_add(offset)
+                    .ok_or(Error::Overflow { ip: self.ip })?;
+            },
+            Opcode::Halt => {
+                self.halted = true;
+                return Ok(Step::Halted);
+            },
+        }
+        self.ip = next;
+        Ok(Step::Continue)
+    }
+
+    /// Run until the machine needs an input that isn't queued, produces an
+    /// output or halts.
+    pub fn run(&mut self) -> Result<Exit, Error> {
+        loop {
+            match self.step()? {
+                Step::Continue => {},
+                Step::NeedInput => return Ok(Exit::NeedInput),
+                Step::Output(value) => return Ok(Exit::Output(value)),
+                Step::Halted => return Ok(Exit::Halted),
+            }
+        }
+    }
+
+    /// Run with the given inputs until the machine halts or runs out of
+    /// input, returning every output.
+    pub fn run_with_input<I: IntoIterator<Item = i64>>(&mut self, input: I) -> Result<Vec<i64>, Error> {
+        self.extend_input(input);
+        let mut output = Vec::new();
+        loop {
+            match self.run()? {
+                Exit::Output(value) => output.push(value),
+                Exit::NeedInput | Exit::Halted => return Ok(output),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_program;
+
+    fn run(program: &str, input: &[i64]) -> Vec<i64> {
+        Vm::new(parse_program(program).unwrap())
+            .run_with_input(input.iter().copied())
+            .unwrap()
+    }
+
+    #[test]
+    fn day2() {
+        let mut vm = Vm::new(vec![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50]);
+        assert_eq!(vm.run(), Ok(Exit::Halted));
+        assert_eq!(vm.read(0), 3500);
+        assert_eq!(vm.step(), Err(Error::Halted));
+    }
+
+    #[test]
+    fn comparisons_and_jumps() {
+        let eq8 = "3,9,8,9,10,9,4,9,99,-1,8";
+        assert_eq!(run(eq8, &[8]), vec![1]);
+        assert_eq!(run(eq8, &[7]), vec![0]);
+        let lt8 = "3,3,1107,-1,8,3,4,3,99";
+        assert_eq!(run(lt8, &[5]), vec![1]);
+        let nonzero = "3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9";
+        assert_eq!(run(nonzero, &[0]), vec![0]);
+        assert_eq!(run(nonzero, &[3]), vec![1]);
+
+        let cmp8 = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,\
+                    1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,\
+                    999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99";
+        assert_eq!(run(cmp8, &[7]), vec![999]);
+        assert_eq!(run(cmp8, &[8]), vec![1000]);
+        assert_eq!(run(cmp8, &[9]), vec![1001]);
+    }
+
+    #[test]
+    fn relative_base_and_memory() {
+        let quine = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+        assert_eq!(run(quine, &[]), parse_program(quine).unwrap());
+        assert_eq!(run("104,1125899906842624,99", &[]), vec![1125899906842624]);
+        assert_eq!(run("1102,34915192,34915192,7,4,7,99,0", &[]), vec![1219070632396864]);
+    }
+
+    #[test]
+    fn pauses() {
+        let mut vm = Vm::new(parse_program("3,0,4,0,3,0,4,0,99").unwrap());
+        assert_eq!(vm.run(), Ok(Exit::NeedInput));
+        assert_eq!(vm.ip(), 0);
+        vm.push_input(5);
+        assert_eq!(vm.run(), Ok(Exit::Output(5)));
+        assert_eq!(vm.run(), Ok(Exit::NeedInput));
+        vm.push_input(6);
+        assert_eq!(vm.run_with_input([]), Ok(vec![6]));
+        assert!(vm.is_halted());
+    }
+
+    #[test]
+    fn errors() {
+        let mut vm = Vm::new(vec![204, -1, 99]);
+        assert_eq!(vm.run(), Err(Error::NegativeAddress { ip: 0, addr: -1 }));
+        let mut vm = Vm::new(vec![1105, 1, -4]);
+        assert_eq!(vm.run(), Err(Error::InvalidJump { ip: 0, target: -4 }));
+        let mut vm = Vm::new(vec![42]);
+        assert!(matches!(vm.run(), Err(Error::Decode { ip: 0, .. })));
+
+        let mut vm = Vm::new(vec![1101, i64::MAX, 1, 0, 99]);
+        assert_eq!(vm.run(), Err(Error::Overflow { ip: 0 }));
+        let mut vm = Vm::new(vec![1102, 1 << 32, 1 << 32, 0, 99]);
+        assert_eq!(vm.run(), Err(Error::Overflow { ip: 0 }));
+        let mut vm = Vm::new(vec![109, i64::MAX, 109, 1, 99]);
+        assert_eq!(vm.run(), Err(Error::Overflow { ip: 2 }));
+        let mut vm = Vm::new(vec![109, i64::MAX, 204, 1, 99]);
+        assert_eq!(vm.run(), Err(Error::Overflow { ip: 2 }));
+
+        let huge = 1 << 60;
+        let mut vm = Vm::new(vec![1101, 1, 2, huge, 99]);
+        assert_eq!(vm.run(), Err(Error::AddressTooLarge { ip: 0, addr: huge as usize }));
+        assert_eq!(vm.memory().len(), 5);
+        let mut vm = Vm::new(vec![3, huge, 99]);
+        assert_eq!(vm.run_with_input([7]), Err(Error::AddressTooLarge { ip: 0, addr: huge as usize }));
+        let last = Vm::MAX_MEMORY as i64 - 1;
+        let mut vm = Vm::new(vec![1101, 1, 2, last, 99]);
+        assert_eq!(vm.run(), Ok(Exit::Halted));
+        assert_eq!(vm.read(Vm::MAX_MEMORY - 1), 3);
+    }
+}
@@ -0,0 +1,184 @@
+//! Finding many patterns in a text at once with an Aho–Corasick automaton.
+//!
+//! The automaton works on bytes, but since both the patterns and the text
+//! are `str`s, every match starts and ends on a character boundary.
+
+use std::collections::VecDeque;
+
+const ROOT: usize = 0;
+
+/// A match of one of the patterns.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct Match<'a, V> {
+    /// The byte offset of the start of the match.
+    pub start: usize,
+    /// The byte offset just after the end of the match.
+    pub end: usize,
+    pub value: &'a V,
+}
+
+impl<V> Match<'_, V> {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+struct State {
+    next: [usize; 256],
+    /// The patterns that end in this state, including those that are a
+    /// suffix of another pattern.
+    output: Vec<usize>,
+}
+
+/// A set of patterns, each with an associated value.
+pub struct Matcher<V> {
+    states: Vec<State>,
+    /// The length and value of each pattern.
+    patterns: Vec<(usize, V)>,
+    max_len: usize,
+}
+
+impl<V> Matcher<V> {
+    /// Build a matcher from pairs of pattern and value. Empty patterns never
+    /// match.
+    pub fn new<P, I>(patterns: I) -> Self
+    where
+        P: AsRef<str>,
+        I: IntoIterator<Item = (P, V)>,
+    {
+        let mut states = vec![State {
+            next: [ROOT; 256],
+            output: Vec::new(),
+        }];
+        // Until the failure links are computed, `ROOT` means that there is
+        // no edge, since no edge leads back to the root in the trie.
+        let mut values = Vec::new();
+        for (i, (pattern, value)) in patterns.into_iter().enumerate() {
+            let pattern = pattern.as_ref().as_bytes();
+            values.push((pattern.len(), value));
+            if pattern.is_empty() {
+                continue;
+            }
+            let mut state = ROOT;
+            for &byte in pattern {
+                if states[state].next[byte as usize] == ROOT {
+                    states[state].next[byte as usize] = states.len();
+                    states.push(State {
+                        next: [ROOT; 256],
+                        output: Vec::new(),
+                    });
+                }
+                state = states[state].next[byte as usize];
+            }
+            states[state].output.push(i);
+        }
+
+        // Turn the trie into a complete automaton in breadth-first order, so
+        // the failure state of every state is done before the state itself.
+        let mut fail = vec![ROOT; states.len()];
+        let mut queue: VecDeque<usize> = states[ROOT].next.iter()
+            .copied()
+            .filter(|&child| child != ROOT)
+            .collect();
+        while let Some(state) = queue.pop_front() {
+            let fail_output = states[fail[state]].output.clone();
+            states[state].output.extend(fail_output);
+            for byte in 0..256 {
+                let child = states[state].next[byte];
+                let fallback = states[fail[state]].next[byte];
+                if child == ROOT {
+                    states[state].next[byte] = fallback;
+                } else {
+                    fail[child] = fallback;
+                    queue.push_back(child);
+                }
+            }
+        }
+
+        let max_len = values.iter().map(|&(len, _)| len).max().unwrap_or(0);
+        Matcher {
+            states,
+            patterns: values,
+            max_len,
+        }
+    }
+
+    /// Every match in the text, including overlapping ones, ordered by
+    /// where they end.
+    pub fn find_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Match<'a, V>> + 'a {
+        let mut state = ROOT;
+        text.bytes().enumerate().flat_map(move |(i, byte)| {
+            state = self.states[state].next[byte as usize];
+            self.states[state].output.iter().map(move |&pattern| {
+                let (len, value) = &self.patterns[pattern];
+                Match {
+                    start: i + 1 - len,
+                    end: i + 1,
+                    value,
+                }
+            })
+        })
+    }
+
+    /// The match that starts first. Of several matches starting at the same
+    /// place, the longest one is returned.
+    pub fn first<'a>(&'a self, text: &'a str) -> Option<Match<'a, V>> {
+        let mut best: Option<Match<'a, V>> = None;
+        for m in self.find_iter(text) {
+            if let Some(b) = &best {
+                // No later match can start before the best one.
+                if m.end > b.start + self.max_len {
+                    break;
+                }
+            }
+            if best.as_ref().is_none_or(|b| (m.start, b.len()) < (b.start, m.len())) {
+                best = Some(m);
+            }
+        }
+        best
+    }
+
+    /// The match that starts last. Of several matches starting at the same
+    /// place, the longest one is returned.
+    pub fn last<'a>(&'a self, text: &'a str) -> Option<Match<'a, V>> {
+        self.find_iter(text).max_by_key(|m| (m.start, m.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlapping() {
+        let matcher = Matcher::new([("he", 1), ("she", 2), ("his", 3), ("hers", 4)]);
+        let matches: Vec<(usize, usize, i32)> = matcher.find_iter("ushers")
+            .map(|m| (m.start, m.end, *m.value))
+            .collect();
+        assert_eq!(matches, vec![(1, 4, 2), (2, 4, 1), (2, 6, 4)]);
+    }
+
+    #[test]
+    fn first_and_last() {
+        let matcher = Matcher::new([("one", 1), ("two", 2), ("eight", 8), ("1", 1)]);
+        assert_eq!(matcher.first("xtwone1eightwo").map(|m| *m.value), Some(2));
+        assert_eq!(matcher.last("xtwone1eightwo").map(|m| (m.start, *m.value)), Some((11, 2)));
+        assert_eq!(matcher.first("nothing"), None);
+
+        let matcher = Matcher::new([("bc", 1), ("abcd", 2)]);
+        assert_eq!(matcher.first("abcd").map(|m| *m.value), Some(2));
+    }
+
+    #[test]
+    fn unicode() {
+        let matcher = Matcher::new([("ø", 'o'), ("é", 'e'), ("", ' ')]);
+        let text = "héllø wørld";
+        let matches: Vec<char> = matcher.find_iter(text).map(|m| *m.value).collect();
+        assert_eq!(matches, vec!['e', 'o', 'o']);
+        let last = matcher.last(text).unwrap();
+        assert_eq!(&text[last.start..last.end], "ø");
+    }
+}
@@ -1,20 +1,8 @@
-type Mapping = Vec<(String, u32)>;
-
-fn first_digit(mut line: &str, mapping: &Mapping) -> u32 {
-    loop {
-        for (pattern, value) in mapping {
-            if line.starts_with(pattern) {
-                return *value;
-            }
-        }
-        line = &line[1..];
-    }
-}
-
+use aoc2023::aho_corasick::Matcher;
 
 fn main() {
-    let file = std::fs::read("inputs/day01.txt").unwrap();
-    let lines: Vec<&str> = std::str::from_utf8(&file).unwrap().lines().collect();
+    let input = aoc2023::load_input(1);
+    let lines: Vec<&str> = input.lines().collect();
 
     let mut values = Vec::new();
     for line in &lines {
@@ -25,7 +13,7 @@ fn main() {
 
     println!("First part: {}", values.iter().copied().sum::<u32>());
 
-    let mapping: Mapping = vec![
+    let matcher = Matcher::new([
         ("one", 1),
         ("two", 2),
         ("three", 3),
@@ -45,14 +33,12 @@ fn main() {
         ("7", 7),
         ("8", 8),
         ("9", 9),
-    ].into_iter().map(|(pat, val)| (pat.to_string(), val)).collect();
-
-    let rev_mapping: Mapping = mapping.iter().map(|(pat, val)| (pat.chars().rev().collect(), *val)).collect();
+    ]);
 
     let mut values2 = Vec::new();
     for line in &lines {
-        let first = first_digit(line, &mapping);
-        let last = first_digit(line.chars().rev().collect::<String>().as_str(), &rev_mapping);
+        let first = matcher.first(line).unwrap().value;
+        let last = matcher.last(line).unwrap().value;
         values2.push(first * 10 + last);
     }
 
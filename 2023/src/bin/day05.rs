@@ -1,31 +1,24 @@
-use aoc2023::parse_iter;
-use aoc2023::range_map::*;
+use aoc_common::tokens::{parse_iter, parse_n};
+use aoc_common::range_map::*;
+use aoc_common::sections::sections;
 
 fn main() {
     let input = aoc2023::load_input(5);
 
-    let mut lines = input.lines();
-    let seeds = lines.next().unwrap().strip_prefix("seeds: ").unwrap();
+    let sections = sections(&input);
+    let seeds = sections[0].header.strip_prefix("seeds: ").unwrap();
     let seeds: Vec<u64> = parse_iter(seeds.split_ascii_whitespace());
 
-    assert!(lines.next().unwrap().is_empty());
-
-    let mut maps = Vec::<RangeMap>::new();
-    let mut curr_builder = RangeMapBuilder::new();
-    for line in lines {
-        if line.ends_with(" map:") { continue; }
-        if line.is_empty() {
-            maps.push(curr_builder.build());
-            curr_builder = RangeMapBuilder::new();
-            continue;
-        }
-
-        let line: Vec<u64> = parse_iter(line.split_ascii_whitespace());
-        curr_builder.add_range(line[1], line[0], line[2]);
-    }
-    if !curr_builder.is_empty() {
-        maps.push(curr_builder.build());
-    }
+    let maps: Vec<RangeMap> = sections[1..].iter()
+        .map(|section| {
+            let mut builder = RangeMapBuilder::new();
+            for line in &section.lines {
+                let [dest, src, len] = parse_n::<u64, 3>(line.split_ascii_whitespace());
+                builder.add_range(src, dest, len);
+            }
+            builder.build()
+        })
+        .collect();
 
     let locations: Vec<_> = seeds.iter()
         .map(|seed| {
@@ -39,18 +32,32 @@ fn main() {
 
     println!("part 1: {}", locations.iter().copied().min().unwrap());
 
-    let mut ranges: Vec<Range> = (0 .. seeds.len() / 2)
+    let ranges: Vec<Range> = (0 .. seeds.len() / 2)
         .map(|i| Range { from: seeds[2*i], len: seeds[2*i+1] })
         .collect();
 
-    for map in &maps {
-        ranges = ranges.into_iter()
-            .flat_map(|r| map.map_range(r)
-                      .into_iter()
-                      .map(|(r, mr)| mr.unwrap_or(r)))
-            .collect();
+    let ranges = normalize(ranges);
+    let reaches_seed = |locations: Range| {
+        preimage_chain(&maps, locations).iter()
+            .any(|pre| ranges.iter().any(|seeds| seeds.intersect(pre).is_some()))
+    };
+
+    // Every location is below u64::MAX, so if this band isn't reached by a
+    // seed, no location is.
+    if !reaches_seed(Range { from: 0, len: u64::MAX }) {
+        println!("part 2: no seed maps to any location");
+        return;
     }
-
-    let min_loc = ranges.iter().map(|r| r.from).min().unwrap();
-    println!("part 2: {}", min_loc);
+    // Find the smallest location whose band [0, loc] is reached by a seed.
+    let mut lo = 0;
+    let mut hi = u64::MAX - 1;
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if reaches_seed(Range { from: 0, len: mid + 1 }) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    println!("part 2: {}", lo);
 }
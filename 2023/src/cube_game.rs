@@ -0,0 +1,230 @@
+//! The cube game of day 2.
+//!
+//! A game is a list of rounds, and in each round the elf draws some number
+//! of cubes of each color from a bag. Colors are not fixed, so any color
+//! name in the input is accepted.
+
+use aoc_common::parse::{scan, ParseError};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// A number of cubes of each color. Colors that are not present count as
+/// zero.
+#[derive(Clone,Debug,Default,Eq,PartialEq)]
+pub struct Bag {
+    cubes: BTreeMap<String, u32>,
+}
+
+impl Bag {
+    pub fn new() -> Self {
+        Bag::default()
+    }
+    pub fn get(&self, color: &str) -> u32 {
+        self.cubes.get(color).copied().unwrap_or(0)
+    }
+    pub fn insert(&mut self, color: &str, count: u32) {
+        self.cubes.insert(color.to_string(), count);
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.cubes.iter().map(|(color, &count)| (color.as_str(), count))
+    }
+    /// Every color in the bag, including those with a count of zero.
+    pub fn colors(&self) -> impl Iterator<Item = &str> {
+        self.cubes.keys().map(String::as_str)
+    }
+    /// The product of the counts of every color in the bag. An empty bag
+    /// has power one.
+    pub fn power(&self) -> u64 {
+        self.cubes.values().map(|&count| u64::from(count)).product()
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<(S, u32)> for Bag {
+    fn from_iter<I: IntoIterator<Item = (S, u32)>>(iter: I) -> Self {
+        let mut bag = Bag::new();
+        for (color, count) in iter {
+            bag.insert(color.as_ref(), count);
+        }
+        bag
+    }
+}
+
+/// A round drawing more cubes of a color than the bag holds.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Violation {
+    /// The index of the round, starting at zero.
+    pub round: usize,
+    pub color: String,
+    pub drawn: u32,
+    pub limit: u32,
+}
+
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Game {
+    pub id: u32,
+    pub rounds: Vec<Bag>,
+}
+
+impl Game {
+    /// The smallest bag that makes every round of the game possible. It
+    /// contains every color in `colors`, with a count of zero if the color
+    /// was never drawn, and any other color that was drawn.
+    pub fn min_bag<'a>(&self, colors: impl IntoIterator<Item = &'a str>) -> Bag {
+        let mut bag: Bag = colors.into_iter().map(|color| (color, 0)).collect();
+        for round in &self.rounds {
+            for (color, count) in round.iter() {
+                let max = bag.cubes.entry(color.to_string()).or_insert(0);
+                *max = u32::max(*max, count);
+            }
+        }
+        bag
+    }
+    /// The power of the minimum bag for `colors`, which is zero if one of
+    /// them was never drawn.
+    pub fn power<'a>(&self, colors: impl IntoIterator<Item = &'a str>) -> u64 {
+        self.min_bag(colors).power()
+    }
+    pub fn is_possible(&self, limits: &Bag) -> bool {
+        self.rounds.iter().all(|round| {
+            round.iter().all(|(color, count)| count <= limits.get(color))
+        })
+    }
+    /// Every draw that exceeds the limits, in order.
+    pub fn violations(&self, limits: &Bag) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for (i, round) in self.rounds.iter().enumerate() {
+            for (color, count) in round.iter() {
+                let limit = limits.get(color);
+                if count > limit {
+                    violations.push(Violation {
+                        round: i,
+                        color: color.to_string(),
+                        drawn: count,
+                        limit,
+                    });
+                }
+            }
+        }
+        violations
+    }
+}
+
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum GameError {
+    /// The line does not look like `Game <id>: <rounds>`.
+    Header(ParseError),
+    /// A draw is not of the form `<count> <color>`.
+    InvalidDraw {
+        round: usize,
+        text: String,
+    },
+    /// The same color appears twice in a round.
+    DuplicateColor {
+        round: usize,
+        color: String,
+    },
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::Header(err) => write!(f, "invalid game header: {}", err),
+            GameError::InvalidDraw { round, text } => {
+                write!(f, "round {}: expected \"<count> <color>\", found {:?}", round, text)
+            },
+            GameError::DuplicateColor { round, color } => {
+                write!(f, "round {}: color {:?} is drawn twice", round, color)
+            },
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+impl FromStr for Game {
+    type Err = GameError;
+
+    /// Parse a line such as `Game 1: 3 blue, 4 red; 1 red, 2 green`.
+    fn from_str(s: &str) -> Result<Game, GameError> {
+        let (id, rounds): (u32, String) = scan("Game {}: {}", s).map_err(GameError::Header)?;
+
+        let mut game = Game {
+            id,
+            rounds: Vec::new(),
+        };
+        for (i, round) in rounds.split_terminator(';').enumerate() {
+            let mut bag = Bag::new();
+            for draw in round.split_terminator(',').map(str::trim) {
+                let invalid = || GameError::InvalidDraw {
+                    round: i,
+                    text: draw.to_string(),
+                };
+                let (count, color) = draw.split_once(' ').ok_or_else(invalid)?;
+                let count = count.parse().map_err(|_| invalid())?;
+                if color.is_empty() || color.contains(char::is_whitespace) {
+                    return Err(invalid());
+                }
+                if bag.cubes.contains_key(color) {
+                    return Err(GameError::DuplicateColor {
+                        round: i,
+                        color: color.to_string(),
+                    });
+                }
+                bag.insert(color, count);
+            }
+            game.rounds.push(bag);
+        }
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let game: Game = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red"
+            .parse()
+            .unwrap();
+        let limits = Bag::from_iter([("red", 12), ("green", 13), ("blue", 14)]);
+
+        assert!(!game.is_possible(&limits));
+        assert_eq!(game.violations(&limits), vec![Violation {
+            round: 0,
+            color: "red".to_string(),
+            drawn: 20,
+            limit: 12,
+        }]);
+        assert_eq!(game.min_bag(limits.colors()), Bag::from_iter([("red", 20), ("green", 13), ("blue", 6)]));
+        assert_eq!(game.power(limits.colors()), 1560);
+    }
+
+    #[test]
+    fn missing_colors() {
+        let colors = ["red", "green", "blue"];
+        let game: Game = "Game 1: 3 blue, 4 red; 1 red, 5 blue".parse().unwrap();
+        assert_eq!(game.min_bag(colors).get("green"), 0);
+        assert_eq!(game.power(colors), 0);
+        assert_eq!(game.power(["red", "blue"]), 20);
+
+        let empty: Game = "Game 2: ".parse().unwrap();
+        assert!(empty.rounds.is_empty());
+        assert_eq!(empty.min_bag(colors), Bag::from_iter(colors.map(|color| (color, 0))));
+        assert_eq!(empty.power(colors), 0);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!("Game 1: 3 blue, x red".parse::<Game>(), Err(GameError::InvalidDraw {
+            round: 0,
+            text: "x red".to_string(),
+        }));
+        assert_eq!("Game 1: 3 blue; 1 red, 2 red".parse::<Game>(), Err(GameError::DuplicateColor {
+            round: 1,
+            color: "red".to_string(),
+        }));
+        assert!(matches!("Round 1: 3 blue".parse::<Game>(), Err(GameError::Header(_))));
+    }
+}
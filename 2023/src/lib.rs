@@ -1,17 +1,16 @@
+use aoc_common::input::Normalize;
+use std::path::Path;
 
 pub fn load_input(day: u32) -> String {
-    let path = format!("inputs/day{:02}.txt", day);
-    String::from_utf8(std::fs::read(path).unwrap()).unwrap()
+    load_input_with(day, &Normalize::new())
 }
 
-pub fn parse_iter<'a, T>(iter: impl Iterator<Item = &'a str>) -> Vec<T>
-where
-    T: core::str::FromStr,
-    T::Err: core::fmt::Debug,
-{
-    iter.filter(|s| !s.is_empty())
-        .map(|v| v.parse().unwrap())
-        .collect()
+pub fn load_input_with(day: u32, options: &Normalize) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join(format!("inputs/day{:02}.txt", day));
+    aoc_common::input::read(&path, day, options)
 }
 
-pub mod range_map;
+pub mod aho_corasick;
+pub mod cube_game;
+pub mod scratchcard;
@@ -0,0 +1,225 @@
+//! The scratchcards of day 4.
+//!
+//! Every matching number on a card wins a copy of one of the following
+//! cards. [`cascade`] propagates those copies through a list of cards.
+
+use aoc_common::bitset::BitSet;
+use aoc_common::parse::ParseError;
+use aoc_common::tokens::{try_parse_iter, TokenError};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+/// The winning numbers of a card. They are kept in a bit set when they are
+/// all below [`WinningNumbers::BITSET_LIMIT`], and in a hash set otherwise,
+/// since the bit set takes memory in proportion to its largest number.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum WinningNumbers {
+    Small(BitSet),
+    Large(HashSet<u32>),
+}
+
+impl WinningNumbers {
+    pub const BITSET_LIMIT: u32 = 1 << 16;
+
+    pub fn contains(&self, number: u32) -> bool {
+        match self {
+            WinningNumbers::Small(set) => set.contains(number as usize),
+            WinningNumbers::Large(set) => set.contains(&number),
+        }
+    }
+}
+
+impl FromIterator<u32> for WinningNumbers {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let numbers: Vec<u32> = iter.into_iter().collect();
+        if numbers.iter().all(|&n| n < WinningNumbers::BITSET_LIMIT) {
+            WinningNumbers::Small(numbers.into_iter().map(|n| n as usize).collect())
+        } else {
+            WinningNumbers::Large(numbers.into_iter().collect())
+        }
+    }
+}
+
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Card {
+    pub id: u32,
+    pub winning: WinningNumbers,
+    pub numbers: Vec<u32>,
+}
+
+impl Card {
+    /// The number of the card's numbers that are winning numbers.
+    pub fn matches(&self) -> usize {
+        self.numbers.iter()
+            .filter(|&&n| self.winning.contains(n))
+            .count()
+    }
+    /// One point for the first match, doubled for every further match.
+    /// Returns `None` if the points don't fit in a `u64`, which takes more
+    /// than 64 matches.
+    pub fn points(&self) -> Option<u64> {
+        match self.matches() {
+            0 => Some(0),
+            n => u32::try_from(n - 1).ok().and_then(|shift| 1u64.checked_shl(shift)),
+        }
+    }
+}
+
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum CardError {
+    Header(ParseError),
+    Numbers(TokenError),
+}
+
+impl fmt::Display for CardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CardError::Header(err) => write!(f, "invalid card: {}", err),
+            CardError::Numbers(err) => write!(f, "invalid number: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for CardError {}
+
+impl FromStr for Card {
+    type Err = CardError;
+
+    /// Parse a line such as `Card 1: 41 48 83 | 83 86  6`.
+    fn from_str(s: &str) -> Result<Card, CardError> {
+        let (id, winning, numbers): (u32, String, String) =
+            aoc_common::parse::scan("Card {}: {} | {}", s).map_err(CardError::Header)?;
+        let winning: Vec<u32> = try_parse_iter(winning.split_ascii_whitespace())
+            .map_err(CardError::Numbers)?;
+        Ok(Card {
+            id,
+            winning: winning.into_iter().collect(),
+            numbers: try_parse_iter(numbers.split_ascii_whitespace())
+                .map_err(CardError::Numbers)?,
+        })
+    }
+}
+
+/// An item that wins copies of items past the end of the list.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct CascadeError {
+    /// The index of the item.
+    pub index: usize,
+    pub wins: usize,
+    /// The number of items after this one.
+    pub remaining: usize,
+}
+
+impl fmt::Display for CascadeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "item {} wins {} copies, but only {} items follow it",
+               self.index, self.wins, self.remaining)
+    }
+}
+
+impl std::error::Error for CascadeError {}
+
+/// Given that every copy of item `i` wins one copy of each of the
+/// `wins[i]` items that follow it, count how many copies of each item there
+/// are when starting with one of each.
+pub fn cascade(wins: &[usize]) -> Result<Vec<u64>, CascadeError> {
+    let mut copies = vec![1u64; wins.len()];
+    for (i, &n) in wins.iter().enumerate() {
+        let remaining = wins.len() - i - 1;
+        if n > remaining {
+            return Err(CascadeError {
+                index: i,
+                wins: n,
+                remaining,
+            });
+        }
+        for j in (i + 1)..=(i + n) {
+            copies[j] += copies[i];
+        }
+    }
+    Ok(copies)
+}
+
+/// What happened to a card after all copies have been won.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct CardReport {
+    pub id: u32,
+    pub matches: usize,
+    /// The number of copies of this card, including the original.
+    pub copies: u64,
+    /// The number of cards that the copies of this card won in total.
+    pub copies_won: u64,
+}
+
+/// Run the cascade on the cards and report on every card.
+pub fn reports(cards: &[Card]) -> Result<Vec<CardReport>, CascadeError> {
+    let matches: Vec<usize> = cards.iter().map(Card::matches).collect();
+    let copies = cascade(&matches)?;
+    Ok(cards.iter()
+        .zip(matches)
+        .zip(copies)
+        .map(|((card, matches), copies)| CardReport {
+            id: card.id,
+            matches,
+            copies,
+            copies_won: copies * matches as u64,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+    #[test]
+    fn example() {
+        let cards: Vec<Card> = EXAMPLE.lines().map(|line| line.parse().unwrap()).collect();
+        assert_eq!(cards.iter().map(Card::points).sum::<Option<u64>>(), Some(13));
+
+        let reports = reports(&cards).unwrap();
+        let copies: Vec<u64> = reports.iter().map(|r| r.copies).collect();
+        assert_eq!(copies, vec![1, 2, 4, 8, 14, 1]);
+        assert_eq!(reports[1].copies_won, 4);
+    }
+
+    #[test]
+    fn large_numbers() {
+        let card: Card = "Card 1: 4000000000 5 | 5 4000000000 7".parse().unwrap();
+        assert!(matches!(card.winning, WinningNumbers::Large(_)));
+        assert_eq!(card.matches(), 2);
+        let card: Card = "Card 2: 65535 5 | 5 7".parse().unwrap();
+        assert!(matches!(card.winning, WinningNumbers::Small(_)));
+        assert_eq!(card.matches(), 1);
+    }
+
+    #[test]
+    fn many_matches() {
+        let card = |matches: u32| {
+            let numbers: Vec<String> = (0..matches).map(|n| n.to_string()).collect();
+            let line = format!("Card 1: {0} | {0}", numbers.join(" "));
+            line.parse::<Card>().unwrap()
+        };
+        assert_eq!(card(0).points(), Some(0));
+        assert_eq!(card(64).points(), Some(1 << 63));
+        assert_eq!(card(65).points(), None);
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        assert_eq!(cascade(&[1, 2, 0]), Err(CascadeError {
+            index: 1,
+            wins: 2,
+            remaining: 1,
+        }));
+        assert_eq!(cascade(&[]), Ok(vec![]));
+    }
+}
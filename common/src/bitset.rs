@@ -0,0 +1,397 @@
+//! Sets of small integers stored as bits.
+//!
+//! [`FixedBitSet`] has a capacity fixed at compile time and lives on the
+//! stack, while [`BitSet`] grows as needed. Both iterate in increasing
+//! order.
+
+use std::fmt;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign};
+
+const BITS: usize = u64::BITS as usize;
+
+/// Iterator over the members of a bit set, in increasing order.
+#[derive(Clone,Debug)]
+pub struct Iter<'a> {
+    words: &'a [u64],
+    /// The index of `word` in `words`.
+    idx: usize,
+    /// The bits of the current word that have not been returned yet.
+    word: u64,
+}
+
+impl<'a> Iter<'a> {
+    fn new(words: &'a [u64]) -> Self {
+        Iter {
+            words,
+            idx: 0,
+            word: words.first().copied().unwrap_or(0),
+        }
+    }
+}
+
+impl Iterator for Iter<'_> {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        while self.word == 0 {
+            self.idx += 1;
+            self.word = *self.words.get(self.idx)?;
+        }
+        let bit = self.word.trailing_zeros() as usize;
+        self.word &= self.word - 1;
+        Some(self.idx * BITS + bit)
+    }
+}
+
+/// Methods that only need the words of the set.
+macro_rules! common_methods {
+    () => {
+        pub fn contains(&self, value: usize) -> bool {
+            self.words().get(value / BITS).is_some_and(|w| w & (1 << (value % BITS)) != 0)
+        }
+        /// The number of members.
+        pub fn len(&self) -> usize {
+            self.words().iter().map(|w| w.count_ones() as usize).sum()
+        }
+        pub fn is_empty(&self) -> bool {
+            self.words().iter().all(|&w| w == 0)
+        }
+        /// The members in increasing order.
+        pub fn iter(&self) -> Iter<'_> {
+            Iter::new(self.words())
+        }
+        /// The smallest member.
+        pub fn first(&self) -> Option<usize> {
+            self.iter().next()
+        }
+        /// Remove and return the smallest member.
+        pub fn pop_first(&mut self) -> Option<usize> {
+            let first = self.first()?;
+            self.remove(first);
+            Some(first)
+        }
+        pub fn is_subset(&self, other: &Self) -> bool {
+            self.iter().all(|value| other.contains(value))
+        }
+        pub fn is_disjoint(&self, other: &Self) -> bool {
+            self.intersection(other).is_empty()
+        }
+        pub fn union(&self, other: &Self) -> Self {
+            let mut res = self.clone();
+            res |= other;
+            res
+        }
+        pub fn intersection(&self, other: &Self) -> Self {
+            let mut res = self.clone();
+            res &= other;
+            res
+        }
+        pub fn difference(&self, other: &Self) -> Self {
+            let mut res = self.clone();
+            res -= other;
+            res
+        }
+        pub fn symmetric_difference(&self, other: &Self) -> Self {
+            let mut res = self.clone();
+            res ^= other;
+            res
+        }
+    };
+}
+
+/// Trait implementations shared by both kinds of set.
+macro_rules! common_impls {
+    ([$($generics:tt)*] $set:ty) => {
+        impl<$($generics)*> Extend<usize> for $set {
+            fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+                for value in iter {
+                    self.insert(value);
+                }
+            }
+        }
+        impl<$($generics)*> FromIterator<usize> for $set {
+            fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+                let mut set = Self::new();
+                set.extend(iter);
+                set
+            }
+        }
+        impl<'a, $($generics)*> IntoIterator for &'a $set {
+            type Item = usize;
+            type IntoIter = Iter<'a>;
+            fn into_iter(self) -> Iter<'a> {
+                self.iter()
+            }
+        }
+        impl<$($generics)*> fmt::Debug for $set {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_set().entries(self.iter()).finish()
+            }
+        }
+        impl<$($generics)*> BitOr for &$set {
+            type Output = $set;
+            fn bitor(self, other: Self) -> $set {
+                self.union(other)
+            }
+        }
+        impl<$($generics)*> BitAnd for &$set {
+            type Output = $set;
+            fn bitand(self, other: Self) -> $set {
+                self.intersection(other)
+            }
+        }
+        impl<$($generics)*> Sub for &$set {
+            type Output = $set;
+            fn sub(self, other: Self) -> $set {
+                self.difference(other)
+            }
+        }
+        impl<$($generics)*> BitXor for &$set {
+            type Output = $set;
+            fn bitxor(self, other: Self) -> $set {
+                self.symmetric_difference(other)
+            }
+        }
+    };
+}
+
+/// A set of integers below `64 * WORDS`.
+#[derive(Copy,Clone,Eq,PartialEq,Hash)]
+pub struct FixedBitSet<const WORDS: usize> {
+    words: [u64; WORDS],
+}
+
+/// A set of integers below 128.
+pub type BitSet128 = FixedBitSet<2>;
+/// A set of integers below 256, such as bytes.
+pub type BitSet256 = FixedBitSet<4>;
+
+impl<const WORDS: usize> Default for FixedBitSet<WORDS> {
+    fn default() -> Self {
+        FixedBitSet { words: [0; WORDS] }
+    }
+}
+
+impl<const WORDS: usize> FixedBitSet<WORDS> {
+    pub const CAPACITY: usize = WORDS * BITS;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+    fn words(&self) -> &[u64] {
+        &self.words
+    }
+    /// Returns whether the value was newly inserted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not below the capacity.
+    pub fn insert(&mut self, value: usize) -> bool {
+        assert!(value < Self::CAPACITY, "{} does not fit in the bit set.", value);
+        let word = &mut self.words[value / BITS];
+        let bit = 1 << (value % BITS);
+        let new = *word & bit == 0;
+        *word |= bit;
+        new
+    }
+    /// Returns whether the value was present.
+    pub fn remove(&mut self, value: usize) -> bool {
+        match self.words.get_mut(value / BITS) {
+            Some(word) => {
+                let bit = 1 << (value % BITS);
+                let present = *word & bit != 0;
+                *word &= !bit;
+                present
+            },
+            None => false,
+        }
+    }
+    pub fn clear(&mut self) {
+        self.words = [0; WORDS];
+    }
+    common_methods!();
+}
+
+impl<const WORDS: usize> BitOrAssign<&FixedBitSet<WORDS>> for FixedBitSet<WORDS> {
+    fn bitor_assign(&mut self, other: &Self) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+}
+impl<const WORDS: usize> BitAndAssign<&FixedBitSet<WORDS>> for FixedBitSet<WORDS> {
+    fn bitand_assign(&mut self, other: &Self) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= b;
+        }
+    }
+}
+impl<const WORDS: usize> SubAssign<&FixedBitSet<WORDS>> for FixedBitSet<WORDS> {
+    fn sub_assign(&mut self, other: &Self) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= !b;
+        }
+    }
+}
+impl<const WORDS: usize> BitXorAssign<&FixedBitSet<WORDS>> for FixedBitSet<WORDS> {
+    fn bitxor_assign(&mut self, other: &Self) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a ^= b;
+        }
+    }
+}
+
+/// A set of integers that grows to fit its largest member.
+#[derive(Clone,Default)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// A set that can hold values below `capacity` without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        BitSet {
+            words: vec![0; capacity.div_ceil(BITS)],
+        }
+    }
+    fn words(&self) -> &[u64] {
+        &self.words
+    }
+    /// Returns whether the value was newly inserted.
+    pub fn insert(&mut self, value: usize) -> bool {
+        let idx = value / BITS;
+        if idx >= self.words.len() {
+            self.words.resize(idx + 1, 0);
+        }
+        let bit = 1 << (value % BITS);
+        let new = self.words[idx] & bit == 0;
+        self.words[idx] |= bit;
+        new
+    }
+    /// Returns whether the value was present.
+    pub fn remove(&mut self, value: usize) -> bool {
+        match self.words.get_mut(value / BITS) {
+            Some(word) => {
+                let bit = 1 << (value % BITS);
+                let present = *word & bit != 0;
+                *word &= !bit;
+                present
+            },
+            None => false,
+        }
+    }
+    pub fn clear(&mut self) {
+        self.words.clear();
+    }
+    /// The words without trailing zero words, so that sets with different
+    /// allocations can be compared.
+    fn trimmed(&self) -> &[u64] {
+        let len = self.words.iter().rposition(|&w| w != 0).map_or(0, |i| i + 1);
+        &self.words[..len]
+    }
+    common_methods!();
+}
+
+impl PartialEq for BitSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.trimmed() == other.trimmed()
+    }
+}
+
+impl Eq for BitSet {}
+
+impl std::hash::Hash for BitSet {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.trimmed().hash(state);
+    }
+}
+
+impl BitOrAssign<&BitSet> for BitSet {
+    fn bitor_assign(&mut self, other: &Self) {
+        if self.words.len() < other.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+}
+impl BitAndAssign<&BitSet> for BitSet {
+    fn bitand_assign(&mut self, other: &Self) {
+        self.words.truncate(other.words.len());
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= b;
+        }
+    }
+}
+impl SubAssign<&BitSet> for BitSet {
+    fn sub_assign(&mut self, other: &Self) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= !b;
+        }
+    }
+}
+impl BitXorAssign<&BitSet> for BitSet {
+    fn bitxor_assign(&mut self, other: &Self) {
+        if self.words.len() < other.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a ^= b;
+        }
+    }
+}
+
+common_impls!([const WORDS: usize] FixedBitSet<WORDS>);
+common_impls!([] BitSet);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed() {
+        let mut set = BitSet128::new();
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        set.extend([127, 64, 0]);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 3, 64, 127]);
+        assert_eq!(set.len(), 4);
+        assert!(set.remove(0));
+        assert!(!set.contains(0));
+        assert!(!set.contains(1000));
+        assert_eq!(set.pop_first(), Some(3));
+        assert_eq!(format!("{:?}", set), "{64, 127}");
+    }
+
+    #[test]
+    #[should_panic]
+    fn fixed_overflow() {
+        BitSet256::new().insert(256);
+    }
+
+    #[test]
+    fn algebra() {
+        let a: BitSet = [1, 2, 3, 200].into_iter().collect();
+        let b: BitSet = [2, 3, 4].into_iter().collect();
+        let set = |values: &[usize]| values.iter().copied().collect::<BitSet>();
+        assert_eq!(&a | &b, set(&[1, 2, 3, 4, 200]));
+        assert_eq!(&a & &b, set(&[2, 3]));
+        assert_eq!(&a - &b, set(&[1, 200]));
+        assert_eq!(&a ^ &b, set(&[1, 4, 200]));
+        assert!(set(&[2, 3]).is_subset(&a));
+        assert!(!a.is_disjoint(&b));
+        assert_eq!(BitSet::with_capacity(1000), BitSet::new());
+        assert_eq!((&a & &b).len(), 2);
+    }
+
+    #[test]
+    fn fixed_algebra() {
+        let a: BitSet256 = [1, 2, 255].into_iter().collect();
+        let b: BitSet256 = [2, 100].into_iter().collect();
+        assert_eq!((&a | &b).iter().collect::<Vec<_>>(), vec![1, 2, 100, 255]);
+        assert_eq!((&a - &b).iter().collect::<Vec<_>>(), vec![1, 255]);
+    }
+}
@@ -0,0 +1,280 @@
+//! Export graphs and trees for viewing with other tools.
+//!
+//! Anything implementing [`Diagram`] can be written as Graphviz DOT or as an
+//! indented text tree. A [`Highlight`] marks a computed set of nodes, an
+//! order or a path in either format.
+
+use crate::graph::{Graph, NodeId};
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+use std::io;
+use std::path::Path;
+
+/// A graph whose nodes are numbered from zero.
+pub trait Diagram {
+    fn node_count(&self) -> usize;
+    fn label(&self, node: usize) -> String;
+    /// The nodes that `node` has an edge to.
+    fn successors(&self, node: usize) -> Vec<usize>;
+    fn edge_label(&self, _from: usize, _to: usize) -> Option<String> {
+        None
+    }
+    /// An undirected diagram lists every edge from both ends.
+    fn is_directed(&self) -> bool {
+        true
+    }
+}
+
+/// Nodes to draw attention to.
+#[derive(Clone,Debug,Default)]
+pub struct Highlight {
+    /// The position of every highlighted node.
+    rank: HashMap<usize, usize>,
+    numbered: bool,
+    path: bool,
+}
+
+impl Highlight {
+    pub fn none() -> Self {
+        Highlight::default()
+    }
+    /// Highlight a set of nodes.
+    pub fn nodes<I: IntoIterator<Item = usize>>(nodes: I) -> Self {
+        Highlight {
+            rank: nodes.into_iter().map(|node| (node, 0)).collect(),
+            ..Highlight::default()
+        }
+    }
+    /// Highlight the nodes and number them, such as with a computed order.
+    pub fn order<I: IntoIterator<Item = usize>>(nodes: I) -> Self {
+        Highlight {
+            rank: nodes.into_iter().enumerate().map(|(i, node)| (node, i)).collect(),
+            numbered: true,
+            path: false,
+        }
+    }
+    /// Highlight the nodes of a path and the edges between them.
+    pub fn path<I: IntoIterator<Item = usize>>(nodes: I) -> Self {
+        Highlight {
+            path: true,
+            ..Highlight::order(nodes)
+        }
+    }
+
+    pub fn contains(&self, node: usize) -> bool {
+        self.rank.contains_key(&node)
+    }
+    /// Whether the edge joins two consecutive nodes of a highlighted path.
+    /// A directed edge only counts in the direction of the path.
+    fn contains_edge(&self, from: usize, to: usize, directed: bool) -> bool {
+        match (self.rank.get(&from), self.rank.get(&to)) {
+            (Some(&a), Some(&b)) => self.path && (a + 1 == b || !directed && b + 1 == a),
+            _ => false,
+        }
+    }
+    /// The text to put in front of a node's label.
+    fn marker(&self, node: usize) -> Option<String> {
+        let rank = self.rank.get(&node)?;
+        if self.numbered {
+            Some(format!("{}", rank + 1))
+        } else {
+            Some(String::from("*"))
+        }
+    }
+}
+
+/// The diagram in Graphviz's DOT language.
+pub fn to_dot<D: Diagram + ?Sized>(diagram: &D, highlight: &Highlight) -> String {
+    let directed = diagram.is_directed();
+    let (keyword, arrow) = if directed { ("digraph", "->") } else { ("graph", "--") };
+    let mut dot = String::new();
+    writeln!(dot, "{} {{", keyword).unwrap();
+    for node in 0..diagram.node_count() {
+        write!(dot, "    n{} [label={}", node, quote(&diagram.label(node))).unwrap();
+        if let Some(marker) = highlight.marker(node) {
+            write!(dot, ", style=filled, fillcolor=gold").unwrap();
+            if highlight.numbered {
+                write!(dot, ", xlabel={}", quote(&marker)).unwrap();
+            }
+        }
+        dot.push_str("];\n");
+    }
+    for from in 0..diagram.node_count() {
+        for to in diagram.successors(from) {
+            if !directed && to < from {
+                continue;
+            }
+            write!(dot, "    n{} {} n{}", from, arrow, to).unwrap();
+            let mut attrs = Vec::new();
+            if let Some(label) = diagram.edge_label(from, to) {
+                attrs.push(format!("label={}", quote(&label)));
+            }
+            if highlight.contains_edge(from, to, directed) {
+                attrs.push(String::from("color=red, penwidth=2"));
+            }
+            if !attrs.is_empty() {
+                write!(dot, " [{}]", attrs.join(", ")).unwrap();
+            }
+            dot.push_str(";\n");
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// A DOT string literal. Only quotes and backslashes need escaping; other
+/// characters, including newlines, are kept as they are.
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The diagram as an indented tree, starting from every node without
+/// incoming edges. A node that was already printed is not expanded again,
+/// so every node is listed once with its children, even in a graph with
+/// cycles. Highlighted nodes are prefixed with their number or a `*`.
+pub fn to_text_tree<D: Diagram + ?Sized>(diagram: &D, highlight: &Highlight) -> String {
+    let n = diagram.node_count();
+    let directed = diagram.is_directed();
+    let mut has_incoming = vec![false; n];
+    if directed {
+        for from in 0..n {
+            for to in diagram.successors(from) {
+                has_incoming[to] = true;
+            }
+        }
+    }
+    // Roots come first, then anything only reachable through a cycle.
+    let starts = (0..n).filter(|&node| !has_incoming[node]).chain(0..n);
+
+    let mut text = String::new();
+    let mut printed = vec![false; n];
+    for start in starts {
+        if printed[start] {
+            continue;
+        }
+        // Each entry holds a node, the prefix of its line and the prefix of
+        // its children's lines.
+        let mut stack = vec![(start, String::new(), String::new())];
+        while let Some((node, prefix, child_prefix)) = stack.pop() {
+            text.push_str(&prefix);
+            if let Some(marker) = highlight.marker(node) {
+                write!(text, "[{}] ", marker).unwrap();
+            }
+            text.push_str(&diagram.label(node));
+            if printed[node] {
+                text.push_str(" (see above)\n");
+                continue;
+            }
+            text.push('\n');
+            printed[node] = true;
+            let children: Vec<usize> = diagram.successors(node).into_iter()
+                .filter(|&child| directed || !printed[child])
+                .collect();
+            for (i, &child) in children.iter().enumerate().rev() {
+                let last = i + 1 == children.len();
+                let (branch, indent) = if last { ("└── ", "    ") } else { ("├── ", "│   ") };
+                stack.push((child, format!("{}{}", child_prefix, branch), format!("{}{}", child_prefix, indent)));
+            }
+        }
+    }
+    text
+}
+
+/// Write the diagram to a file, as DOT if the extension is `dot` or `gv`
+/// and as a text tree otherwise.
+pub fn write<D: Diagram + ?Sized>(path: &Path, diagram: &D, highlight: &Highlight) -> io::Result<()> {
+    let contents = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("dot") | Some("gv") => to_dot(diagram, highlight),
+        _ => to_text_tree(diagram, highlight),
+    };
+    std::fs::write(path, contents)
+}
+
+impl<W: Copy + fmt::Display> Diagram for Graph<W> {
+    fn node_count(&self) -> usize {
+        self.len()
+    }
+    fn label(&self, node: usize) -> String {
+        self.name(NodeId::from_index(node)).to_string()
+    }
+    fn successors(&self, node: usize) -> Vec<usize> {
+        self.neighbors(NodeId::from_index(node)).map(NodeId::index).collect()
+    }
+    fn edge_label(&self, from: usize, to: usize) -> Option<String> {
+        self.edges(NodeId::from_index(from)).iter()
+            .find(|edge| edge.to.index() == to)
+            .map(|edge| edge.weight.to_string())
+    }
+    fn is_directed(&self) -> bool {
+        Graph::is_directed(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deps() -> Graph<u32> {
+        let mut graph = Graph::directed();
+        for (from, to) in [("C", "A"), ("C", "F"), ("A", "B"), ("A", "D"), ("B", "E"), ("D", "E"), ("F", "E")] {
+            graph.add_named_edge(from, to, 1);
+        }
+        graph
+    }
+
+    #[test]
+    fn text_tree() {
+        let graph = deps();
+        let order: Vec<usize> = graph.topological_order().unwrap().into_iter().map(NodeId::index).collect();
+        let text = to_text_tree(&graph, &Highlight::order(order));
+        assert_eq!(text, "\
+[1] C
+├── [2] A
+│   ├── [4] B
+│   │   └── [6] E
+│   └── [5] D
+│       └── [6] E (see above)
+└── [3] F
+    └── [6] E (see above)
+");
+        let mut cycle = Graph::directed();
+        cycle.add_named_edge("x", "y", 1);
+        cycle.add_named_edge("y", "x", 1);
+        assert_eq!(to_text_tree(&cycle, &Highlight::none()), "x\n└── y\n    └── x (see above)\n");
+    }
+
+    #[test]
+    fn dot() {
+        let graph = deps();
+        let path = ["C", "A", "D"].map(|name| graph.id(name).unwrap().index());
+        let dot = to_dot(&graph, &Highlight::path(path));
+        assert!(dot.starts_with("digraph {\n    n0 [label=\"C\", style=filled, fillcolor=gold, xlabel=\"1\"];\n"));
+        assert!(dot.contains("    n0 -> n1 [label=\"1\", color=red, penwidth=2];\n"));
+        assert!(dot.contains("    n1 -> n3 [label=\"1\"];\n"));
+        assert!(dot.contains("    n2 [label=\"F\"];\n"));
+
+        // The reverse of a path edge is not part of the path.
+        let mut back = Graph::directed();
+        back.add_named_edge("a", "b", 1);
+        back.add_named_edge("b", "a", 1);
+        let dot = to_dot(&back, &Highlight::path([0, 1]));
+        assert!(dot.contains("    n0 -> n1 [label=\"1\", color=red, penwidth=2];\n"));
+        assert!(dot.contains("    n1 -> n0 [label=\"1\"];\n"));
+
+        assert_eq!(quote("a \"b\" \\n é"), "\"a \\\"b\\\" \\\\n é\"");
+
+        let mut undirected = Graph::undirected();
+        undirected.add_named_edge("a", "b", 2);
+        assert_eq!(to_dot(&undirected, &Highlight::nodes([1])), "graph {\n    n0 [label=\"a\"];\n    \
+            n1 [label=\"b\", style=filled, fillcolor=gold];\n    n0 -- n1 [label=\"2\"];\n}\n");
+    }
+}
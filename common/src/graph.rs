@@ -0,0 +1,525 @@
+//! Weighted graphs with named nodes.
+//!
+//! Nodes are interned from their names the first time they are seen, and
+//! are afterwards referred to by a [`NodeId`], which is their index in the
+//! order they were added. Edges are stored as adjacency lists. An undirected
+//! graph stores every edge in both lists, but reports it once.
+
+use crate::diagram::{self, Highlight};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+use std::ops::Add;
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash,PartialOrd,Ord)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+    pub(crate) fn from_index(index: usize) -> NodeId {
+        NodeId(index)
+    }
+}
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+pub struct Edge<W> {
+    pub to: NodeId,
+    pub weight: W,
+}
+
+/// A directed graph contains a cycle, so it has no topological order.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct CycleError {
+    /// A node on the cycle.
+    pub node: String,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the graph has a cycle through {:?}", self.node)
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+#[derive(Clone,Debug)]
+pub struct Graph<W = u64> {
+    directed: bool,
+    names: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    adjacency: Vec<Vec<Edge<W>>>,
+}
+
+impl<W> Graph<W> {
+    pub fn directed() -> Self {
+        Self::with_direction(true)
+    }
+    pub fn undirected() -> Self {
+        Self::with_direction(false)
+    }
+    fn with_direction(directed: bool) -> Self {
+        Graph {
+            directed,
+            names: Vec::new(),
+            ids: HashMap::new(),
+            adjacency: Vec::new(),
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+    /// The number of nodes.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+    /// The id of the node called `name`, adding the node if it is new.
+    pub fn node(&mut self, name: &str) -> NodeId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = NodeId(self.names.len());
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.adjacency.push(Vec::new());
+        id
+    }
+    /// The id of the node called `name`, if there is one.
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.names[id.0]
+    }
+    /// Every node, in the order they were added.
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> {
+        (0..self.names.len()).map(NodeId)
+    }
+    /// The edges leaving `id`. In an undirected graph these are all the
+    /// edges touching it.
+    pub fn edges(&self, id: NodeId) -> &[Edge<W>] {
+        &self.adjacency[id.0]
+    }
+    pub fn neighbors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.adjacency[id.0].iter().map(|edge| edge.to)
+    }
+    /// The number of edges, counting an undirected edge once.
+    pub fn edge_count(&self) -> usize {
+        self.adjacency.iter().enumerate()
+            .map(|(from, edges)| {
+                edges.iter().filter(|edge| self.directed || from <= edge.to.0).count()
+            })
+            .sum()
+    }
+}
+
+impl<W: Copy> Graph<W> {
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, weight: W) {
+        self.adjacency[from.0].push(Edge { to, weight });
+        if !self.directed && from != to {
+            self.adjacency[to.0].push(Edge { to: from, weight });
+        }
+    }
+    /// Add an edge between two nodes given by name, adding the nodes if
+    /// they are new.
+    pub fn add_named_edge(&mut self, from: &str, to: &str, weight: W) -> (NodeId, NodeId) {
+        let from = self.node(from);
+        let to = self.node(to);
+        self.add_edge(from, to, weight);
+        (from, to)
+    }
+    /// Every edge as `(from, to, weight)`. An undirected edge is reported
+    /// once, from the node that was added first.
+    pub fn all_edges(&self) -> impl Iterator<Item = (NodeId, NodeId, W)> + '_ {
+        let directed = self.directed;
+        self.nodes().flat_map(move |from| {
+            self.edges(from).iter()
+                .filter(move |edge| directed || from <= edge.to)
+                .map(move |edge| (from, edge.to, edge.weight))
+        })
+    }
+
+    /// The strongly connected components, each listed in no particular
+    /// order. A component comes before every component it has an edge
+    /// into. In an undirected graph these are the connected components.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<NodeId>> {
+        // Tarjan's algorithm, with an explicit stack of the nodes being
+        // visited and how many of their edges have been followed.
+        const UNVISITED: usize = usize::MAX;
+        let n = self.len();
+        let mut index = vec![UNVISITED; n];
+        let mut low = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+        for root in 0..n {
+            if index[root] != UNVISITED {
+                continue;
+            }
+            let mut visiting = vec![(root, 0)];
+            index[root] = next_index;
+            low[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            while let Some(&mut (node, ref mut edge)) = visiting.last_mut() {
+                if let Some(&Edge { to, .. }) = self.adjacency[node].get(*edge) {
+                    *edge += 1;
+                    let to = to.0;
+                    if index[to] == UNVISITED {
+                        index[to] = next_index;
+                        low[to] = next_index;
+                        next_index += 1;
+                        stack.push(to);
+                        on_stack[to] = true;
+                        visiting.push((to, 0));
+                    } else if on_stack[to] {
+                        low[node] = usize::min(low[node], index[to]);
+                    }
+                    continue;
+                }
+                visiting.pop();
+                if let Some(&(parent, _)) = visiting.last() {
+                    low[parent] = usize::min(low[parent], low[node]);
+                }
+                if low[node] == index[node] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component.push(NodeId(member));
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+        // Tarjan's algorithm finds the components in reverse topological
+        // order.
+        components.reverse();
+        components
+    }
+
+    /// The connected components, ignoring the direction of edges. Each
+    /// component is sorted, and the components are ordered by their first
+    /// node.
+    pub fn connected_components(&self) -> Vec<Vec<NodeId>> {
+        let mut sets = DisjointSets::new(self.len());
+        for (from, to, _) in self.all_edges() {
+            sets.union(from.0, to.0);
+        }
+        let mut component_of = HashMap::new();
+        let mut components: Vec<Vec<NodeId>> = Vec::new();
+        for node in self.nodes() {
+            let root = sets.find(node.0);
+            let i = *component_of.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[i].push(node);
+        }
+        components
+    }
+
+    /// Nodes ordered such that every edge goes from an earlier node to a
+    /// later one. Among the nodes that could come next, the one added first
+    /// is picked. Any edge of an undirected graph counts as a cycle.
+    pub fn topological_order(&self) -> Result<Vec<NodeId>, CycleError> {
+        let mut incoming = vec![0; self.len()];
+        for edges in &self.adjacency {
+            for edge in edges {
+                incoming[edge.to.0] += 1;
+            }
+        }
+        let mut ready: BinaryHeap<_> = self.nodes()
+            .filter(|node| incoming[node.0] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(Reverse(node)) = ready.pop() {
+            order.push(node);
+            for next in self.neighbors(node) {
+                incoming[next.0] -= 1;
+                if incoming[next.0] == 0 {
+                    ready.push(Reverse(next));
+                }
+            }
+        }
+        if order.len() < self.len() {
+            let on_cycle = self.strongly_connected_components().into_iter()
+                .find(|component| {
+                    component.len() > 1 || self.neighbors(component[0]).any(|to| to == component[0])
+                })
+                .map_or(NodeId(0), |component| component[0]);
+            return Err(CycleError {
+                node: self.name(on_cycle).to_string(),
+            });
+        }
+        Ok(order)
+    }
+}
+
+impl<W: Copy + Ord + Default + Add<Output = W>> Graph<W> {
+    /// A minimum spanning forest found with Kruskal's algorithm: its total
+    /// weight and its edges. Edge directions are ignored.
+    pub fn minimum_spanning_forest(&self) -> (W, Vec<(NodeId, NodeId, W)>) {
+        let mut edges: Vec<_> = self.all_edges().collect();
+        edges.sort_by_key(|&(_, _, weight)| weight);
+        let mut sets = DisjointSets::new(self.len());
+        let mut total = W::default();
+        let mut forest = Vec::new();
+        for (from, to, weight) in edges {
+            if sets.union(from.0, to.0) {
+                total = total + weight;
+                forest.push((from, to, weight));
+            }
+        }
+        (total, forest)
+    }
+
+    /// The heaviest path in a directed acyclic graph: its weight and its
+    /// nodes. A path may start at any node, so with negative weights it
+    /// leaves out edges that would only make it lighter. An empty graph has
+    /// an empty path.
+    pub fn longest_path(&self) -> Result<(W, Vec<NodeId>), CycleError> {
+        let order = self.topological_order()?;
+        // Every node starts out as a path of its own, with no predecessor.
+        let mut best = vec![(W::default(), None); self.len()];
+        for &node in &order {
+            let (dist, _) = best[node.0];
+            for edge in self.edges(node) {
+                let through = dist + edge.weight;
+                if best[edge.to.0].0 < through {
+                    best[edge.to.0] = (through, Some(node));
+                }
+            }
+        }
+        let end = match order.iter().max_by_key(|node| best[node.0].0) {
+            Some(&end) => end,
+            None => return Ok((W::default(), Vec::new())),
+        };
+        let mut path = vec![end];
+        while let Some(prev) = best[path.last().unwrap().0].1 {
+            path.push(prev);
+        }
+        path.reverse();
+        Ok((best[end.0].0, path))
+    }
+
+    /// A minimum cut found with the Stoer-Wagner algorithm: its weight and
+    /// the nodes on one side of it. Edge directions are ignored. Returns
+    /// `None` if there are fewer than two nodes.
+    ///
+    /// The weights must not be negative. The algorithm relies on that, and
+    /// with negative weights the cut it returns may not be minimal.
+    ///
+    /// Each of the `n - 1` phases orders the remaining nodes with a priority
+    /// queue, so the whole search takes O(n·m·log n) for `m` edges. That is
+    /// fine for sparse graphs of a few thousand nodes.
+    pub fn min_cut(&self) -> Option<(W, Vec<NodeId>)> {
+        let n = self.len();
+        if n < 2 {
+            return None;
+        }
+        let mut weights: Vec<HashMap<usize, W>> = vec![HashMap::new(); n];
+        for (from, to, weight) in self.all_edges() {
+            if from != to {
+                for (a, b) in [(from.0, to.0), (to.0, from.0)] {
+                    let total = weights[a].entry(b).or_default();
+                    *total = *total + weight;
+                }
+            }
+        }
+        // Every remaining node stands for the group of nodes merged into it.
+        let mut groups: Vec<Vec<NodeId>> = self.nodes().map(|node| vec![node]).collect();
+        let mut active: Vec<usize> = (0..n).collect();
+        let mut best: Option<(W, Vec<NodeId>)> = None;
+        let mut connection = vec![W::default(); n];
+        let mut added = vec![false; n];
+        while active.len() > 1 {
+            // Add the nodes one by one, always picking the one most tightly
+            // connected to those already added. The cut between the last
+            // node and the rest is the minimum cut separating it from the
+            // node before it. Stale queue entries are skipped.
+            for &node in &active {
+                connection[node] = W::default();
+                added[node] = false;
+            }
+            let mut queue: BinaryHeap<(W, usize)> = active.iter()
+                .map(|&node| (W::default(), node))
+                .collect();
+            let (mut prev, mut last) = (active[0], active[0]);
+            let mut count = 0;
+            while let Some((weight, next)) = queue.pop() {
+                if added[next] || weight != connection[next] {
+                    continue;
+                }
+                added[next] = true;
+                count += 1;
+                prev = last;
+                last = next;
+                if count == active.len() {
+                    break;
+                }
+                for (&node, &weight) in &weights[next] {
+                    if !added[node] {
+                        connection[node] = connection[node] + weight;
+                        queue.push((connection[node], node));
+                    }
+                }
+            }
+            if best.as_ref().is_none_or(|(weight, _)| connection[last] < *weight) {
+                best = Some((connection[last], groups[last].clone()));
+            }
+
+            // Merge the last node into the one added before it.
+            let group = std::mem::take(&mut groups[last]);
+            groups[prev].extend(group);
+            for (node, weight) in std::mem::take(&mut weights[last]) {
+                weights[node].remove(&last);
+                if node != prev {
+                    let total = weights[prev].entry(node).or_default();
+                    *total = *total + weight;
+                    let total = weights[node].entry(prev).or_default();
+                    *total = *total + weight;
+                }
+            }
+            active.retain(|&node| node != last);
+        }
+        best
+    }
+}
+
+impl<W: Copy + fmt::Display> Graph<W> {
+    /// The graph in Graphviz's DOT language, with weights as edge labels.
+    /// See [`crate::diagram`] for highlighting parts of it.
+    pub fn to_dot(&self) -> String {
+        diagram::to_dot(self, &Highlight::none())
+    }
+}
+
+/// Union-find over `0..n`, with path halving and union by size.
+struct DisjointSets {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> Self {
+        DisjointSets {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+        }
+    }
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+    /// Merge the sets of `a` and `b`. Returns `false` if they were already
+    /// the same set.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names<W>(graph: &Graph<W>, nodes: &[NodeId]) -> Vec<String> {
+        let mut names: Vec<_> = nodes.iter().map(|&node| graph.name(node).to_string()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn components() {
+        let mut graph = Graph::directed();
+        for (from, to) in [("a", "b"), ("b", "c"), ("c", "a"), ("c", "d"), ("d", "e"), ("e", "d"), ("x", "y")] {
+            graph.add_named_edge(from, to, 1u64);
+        }
+        assert_eq!(graph.len(), 7);
+        assert_eq!(graph.edge_count(), 7);
+        let sccs: Vec<_> = graph.strongly_connected_components().iter()
+            .map(|component| names(&graph, component))
+            .collect();
+        assert_eq!(sccs.len(), 4);
+        let abc = sccs.iter().position(|c| c == &["a", "b", "c"]).unwrap();
+        let de = sccs.iter().position(|c| c == &["d", "e"]).unwrap();
+        assert!(abc < de);
+        assert_eq!(graph.connected_components().len(), 2);
+        assert!(graph.topological_order().is_err());
+    }
+
+    #[test]
+    fn spanning_tree_and_longest_path() {
+        let mut graph = Graph::undirected();
+        for (from, to, weight) in [("a", "b", 4), ("a", "c", 1), ("b", "c", 2), ("c", "d", 5), ("b", "d", 3)] {
+            graph.add_named_edge(from, to, weight);
+        }
+        let (total, forest) = graph.minimum_spanning_forest();
+        assert_eq!((total, forest.len()), (6, 3));
+
+        let mut dag = Graph::directed();
+        for (from, to, weight) in [("a", "b", 3), ("a", "c", 1), ("c", "b", 4), ("b", "d", 2), ("a", "d", 6)] {
+            dag.add_named_edge(from, to, weight);
+        }
+        let (length, path) = dag.longest_path().unwrap();
+        assert_eq!(length, 7);
+        assert_eq!(path.iter().map(|&n| dag.name(n)).collect::<Vec<_>>(), ["a", "c", "b", "d"]);
+
+        // A path that starts at b is heavier than one through the light
+        // edge from a.
+        let mut signed = Graph::directed();
+        signed.add_named_edge("a", "b", -5i64);
+        signed.add_named_edge("b", "c", 10);
+        let (length, path) = signed.longest_path().unwrap();
+        assert_eq!(length, 10);
+        assert_eq!(path.iter().map(|&n| signed.name(n)).collect::<Vec<_>>(), ["b", "c"]);
+    }
+
+    #[test]
+    fn min_cut() {
+        let wiring = "jqt: rhn xhk nvd\nrsh: frs pzl lsr\nxhk: hfx\ncmg: qnr nvd lhk bvb\n\
+                      rhn: xhk bvb hfx\nbvb: xhk hfx\npzl: lsr hfx nvd\nqnr: nvd\n\
+                      ntq: jqt hfx bvb xhk\nnvd: lhk\nlsr: lhk\nrzs: qnr cmg lsr rsh\n\
+                      frs: qnr lhk lsr";
+        let mut graph = Graph::undirected();
+        for line in wiring.lines() {
+            let (from, tos) = line.split_once(": ").unwrap();
+            for to in tos.split(' ') {
+                graph.add_named_edge(from, to, 1u32);
+            }
+        }
+        let (weight, side) = graph.min_cut().unwrap();
+        assert_eq!(weight, 3);
+        assert_eq!(side.len() * (graph.len() - side.len()), 54);
+    }
+
+    #[test]
+    fn dot() {
+        let mut graph = Graph::undirected();
+        graph.add_named_edge("a", "b", 2);
+        assert_eq!(graph.to_dot(), "graph {\n    n0 [label=\"a\"];\n    n1 [label=\"b\"];\n    n0 -- n1 [label=\"2\"];\n}\n");
+    }
+}
@@ -0,0 +1,156 @@
+//! Rectangular grids of cells, such as character maps.
+//!
+//! Cells are addressed by a [`Point`] with `y` growing downwards. Lookups
+//! with signed coordinates return `None` outside the grid, so neighbors of
+//! edge cells need no special casing.
+
+use crate::point::{Dir8, Point};
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+#[derive(Clone,Debug,Eq,PartialEq,Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// A line whose length differs from the first line.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct RaggedGrid {
+    /// The index of the line, starting at zero.
+    pub line: usize,
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl fmt::Display for RaggedGrid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {} has length {}, expected {}", self.line, self.found, self.expected)
+    }
+}
+
+impl std::error::Error for RaggedGrid {}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl Grid<u8> {
+    /// The bytes of the non-empty lines of the input.
+    pub fn parse_bytes(input: &str) -> Result<Self, RaggedGrid> {
+        let mut width = 0;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for line in input.lines().filter(|line| !line.is_empty()) {
+            if height == 0 {
+                width = line.len();
+            } else if line.len() != width {
+                return Err(RaggedGrid {
+                    line: height,
+                    expected: width,
+                    found: line.len(),
+                });
+            }
+            cells.extend_from_slice(line.as_bytes());
+            height += 1;
+        }
+        Ok(Grid { width, height, cells })
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    pub fn get(&self, pos: Point<isize>) -> Option<&T> {
+        let idx = self.index_of(pos)?;
+        Some(&self.cells[idx])
+    }
+    pub fn get_mut(&mut self, pos: Point<isize>) -> Option<&mut T> {
+        let idx = self.index_of(pos)?;
+        Some(&mut self.cells[idx])
+    }
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+    /// Every position in the grid, in reading order.
+    pub fn positions(&self) -> impl Iterator<Item = Point<usize>> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+    /// The positions of the up to eight cells surrounding `pos`.
+    pub fn neighbors8(&self, pos: Point<usize>) -> impl Iterator<Item = Point<usize>> {
+        let (width, height) = (self.width, self.height);
+        Dir8::ALL.into_iter().filter_map(move |dir| {
+            let delta = dir.delta::<isize>();
+            let x = pos.x.checked_add_signed(delta.x)?;
+            let y = pos.y.checked_add_signed(delta.y)?;
+            if x < width && y < height {
+                Some(Point::new(x, y))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn index_of(&self, pos: Point<isize>) -> Option<usize> {
+        let x = usize::try_from(pos.x).ok().filter(|&x| x < self.width)?;
+        let y = usize::try_from(pos.y).ok().filter(|&y| y < self.height)?;
+        Some(y * self.width + x)
+    }
+}
+
+impl<T> Index<Point<usize>> for Grid<T> {
+    type Output = T;
+    fn index(&self, pos: Point<usize>) -> &T {
+        assert!(pos.x < self.width, "x = {} is outside the grid", pos.x);
+        &self.cells[pos.y * self.width + pos.x]
+    }
+}
+
+impl<T> IndexMut<Point<usize>> for Grid<T> {
+    fn index_mut(&mut self, pos: Point<usize>) -> &mut T {
+        assert!(pos.x < self.width, "x = {} is outside the grid", pos.x);
+        &mut self.cells[pos.y * self.width + pos.x]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_lookup() {
+        let grid = Grid::parse_bytes("abc\ndef\n\n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point::new(2, 1)], b'f');
+        assert_eq!(grid.get(Point::new(-1, 0)), None);
+        assert_eq!(grid.get(Point::new(0, 2)), None);
+        assert_eq!(grid.row(1), b"def");
+        assert_eq!(Grid::parse_bytes("abc\nde"), Err(RaggedGrid {
+            line: 1,
+            expected: 3,
+            found: 2,
+        }));
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = Grid::new(3, 2, 0);
+        let mut corner: Vec<_> = grid.neighbors8(Point::new(0, 0)).collect();
+        corner.sort();
+        assert_eq!(corner, vec![Point::new(1, 0), Point::new(0, 1), Point::new(1, 1)]);
+        assert_eq!(grid.neighbors8(Point::new(1, 1)).count(), 5);
+        assert_eq!(grid.positions().count(), 6);
+    }
+}
@@ -0,0 +1,163 @@
+//! Decoding and normalizing puzzle inputs.
+//!
+//! Inputs saved by some editors start with a byte order mark or use `\r\n`
+//! line endings. Normalizing them up front means that the solutions only
+//! ever see `\n`.
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const BOM: &[u8] = "\u{feff}".as_bytes();
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct InvalidUtf8 {
+    /// The position of the first invalid byte in the original input.
+    pub offset: usize,
+}
+
+impl fmt::Display for InvalidUtf8 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid UTF-8 at byte offset {}", self.offset)
+    }
+}
+
+impl std::error::Error for InvalidUtf8 {}
+
+/// Options for normalizing an input.
+#[derive(Clone,Debug,Default)]
+pub struct Normalize {
+    trim_trailing_blank_lines: bool,
+}
+
+impl Normalize {
+    pub fn new() -> Self {
+        Normalize::default()
+    }
+
+    /// Remove blank lines at the end of the input. The last line keeps its
+    /// newline.
+    pub fn trim_trailing_blank_lines(mut self, trim: bool) -> Self {
+        self.trim_trailing_blank_lines = trim;
+        self
+    }
+
+    /// Decode the input, strip a byte order mark and turn `\r\n` and lone
+    /// `\r` line endings into `\n`.
+    pub fn apply(&self, bytes: &[u8]) -> Result<String, InvalidUtf8> {
+        let bom_len = if bytes.starts_with(BOM) { BOM.len() } else { 0 };
+        let text = std::str::from_utf8(&bytes[bom_len..]).map_err(|err| InvalidUtf8 {
+            offset: bom_len + err.valid_up_to(),
+        })?;
+
+        let mut out = String::with_capacity(text.len());
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '\r' {
+                chars.next_if_eq(&'\n');
+                out.push('\n');
+            } else {
+                out.push(c);
+            }
+        }
+
+        if self.trim_trailing_blank_lines {
+            match out.rfind(|c: char| !c.is_whitespace()) {
+                Some(last) => {
+                    if let Some(newline) = out[last..].find('\n') {
+                        out.truncate(last + newline + 1);
+                    }
+                },
+                None => out.clear(),
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Normalize the input with the default options.
+pub fn normalize(bytes: &[u8]) -> Result<String, InvalidUtf8> {
+    Normalize::new().apply(bytes)
+}
+
+/// The input of a day could not be loaded.
+#[derive(Debug)]
+pub enum InputError {
+    Read {
+        path: PathBuf,
+        err: io::Error,
+    },
+    Decode {
+        path: PathBuf,
+        err: InvalidUtf8,
+    },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::Read { path, err } => write!(f, "cannot read {}: {}", path.display(), err),
+            InputError::Decode { path, err } => write!(f, "{} has {}", path.display(), err),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Read and normalize an input file.
+pub fn load(path: &Path, options: &Normalize) -> Result<String, InputError> {
+    let bytes = std::fs::read(path).map_err(|err| InputError::Read {
+        path: path.to_path_buf(),
+        err,
+    })?;
+    options.apply(&bytes).map_err(|err| InputError::Decode {
+        path: path.to_path_buf(),
+        err,
+    })
+}
+
+/// Read and normalize the input of a day.
+///
+/// # Panics
+///
+/// Panics if the file is missing or is not valid UTF-8.
+pub fn read(path: &Path, day: u32, options: &Normalize) -> String {
+    match load(path, options) {
+        Ok(text) => text,
+        Err(err) => panic!("Input for day {}: {}.", day, err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_endings() {
+        assert_eq!(normalize(b"\xef\xbb\xbfa\r\nb\rc\n").unwrap(), "a\nb\nc\n");
+        assert_eq!(normalize(b"a\r\n\r\n").unwrap(), "a\n\n");
+    }
+
+    #[test]
+    fn trailing_blank_lines() {
+        let trim = Normalize::new().trim_trailing_blank_lines(true);
+        assert_eq!(trim.apply(b"a \r\n\r\n  \n\n").unwrap(), "a \n");
+        assert_eq!(trim.apply(b"a\n\nb").unwrap(), "a\n\nb");
+        assert_eq!(trim.apply(b"\n \n").unwrap(), "");
+    }
+
+    #[test]
+    fn missing_file() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("no such input.txt");
+        let err = load(&path, &Normalize::new()).unwrap_err();
+        assert!(matches!(&err, InputError::Read { err, .. } if err.kind() == io::ErrorKind::NotFound));
+        assert!(err.to_string().starts_with("cannot read "));
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        assert_eq!(normalize(b"\xef\xbb\xbfab\xffc"), Err(InvalidUtf8 { offset: 5 }));
+        assert_eq!(normalize(b"abc\xe2\x82").unwrap_err().to_string(),
+            "invalid UTF-8 at byte offset 3");
+    }
+}
@@ -0,0 +1,29 @@
+//! Code shared between the solutions of every year.
+
+use std::time::Instant;
+
+pub mod bitset;
+pub mod diagram;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod parse;
+pub mod point;
+pub mod range_map;
+pub mod render;
+pub mod search;
+pub mod sections;
+pub mod tokens;
+pub mod voxel;
+
+/// Run `f` and print how long it took.
+#[inline]
+pub fn time<F, Out>(label: &str, f: F) -> Out
+where
+    F: FnOnce() -> Out,
+{
+    let before = Instant::now();
+    let res = f();
+    println!("{}: {:?}", label, before.elapsed());
+    res
+}
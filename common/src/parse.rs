@@ -0,0 +1,300 @@
+//! Parsing lines against patterns such as `"#{} @ {},{}: {}x{}"`.
+//!
+//! Every `{}` in a pattern is a field, and everything else must match the
+//! input literally. Use `{{` and `}}` for literal braces. A field extends
+//! until the first occurrence of the literal text that follows it, and its
+//! text is trimmed before being parsed with `FromStr`.
+//!
+//! ```
+//! use aoc_common::parse::scan;
+//!
+//! let (id, x, y): (u32, i32, i32) = scan("#{} @ {},{}", "#3 @ 5,-2").unwrap();
+//! assert_eq!((id, x, y), (3, 5, -2));
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct ParseError {
+    /// The column at which the error was found, counted in characters and
+    /// starting at one.
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum ParseErrorKind {
+    /// The input did not contain this literal text of the pattern.
+    ExpectedLiteral(String),
+    /// The input continues after the end of the pattern.
+    TrailingInput,
+    /// A field could not be parsed.
+    InvalidValue {
+        text: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "column {}: ", self.column)?;
+        match &self.kind {
+            ParseErrorKind::ExpectedLiteral(lit) => write!(f, "expected {:?}", lit),
+            ParseErrorKind::TrailingInput => write!(f, "unexpected trailing input"),
+            ParseErrorKind::InvalidValue { text, message } => {
+                write!(f, "invalid value {:?}: {}", text, message)
+            },
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A piece of the input matched by a `{}` in the pattern.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub struct Field<'a> {
+    /// The column of the first character of the field, counted in
+    /// characters and starting at one.
+    pub column: usize,
+    pub text: &'a str,
+}
+
+impl Field<'_> {
+    pub fn parse<T>(&self) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.text.parse().map_err(|err: T::Err| ParseError {
+            column: self.column,
+            kind: ParseErrorKind::InvalidValue {
+                text: self.text.to_string(),
+                message: err.to_string(),
+            },
+        })
+    }
+}
+
+/// Types that can be built from the fields of a pattern. This is implemented
+/// for tuples of types implementing `FromStr`.
+pub trait FromFields: Sized {
+    /// The number of fields.
+    const ARITY: usize;
+    fn from_fields(fields: &[Field<'_>]) -> Result<Self, ParseError>;
+}
+
+macro_rules! tuple_impl {
+    ($arity:expr; $($t:ident $i:tt),*) => {
+        impl<$($t),*> FromFields for ($($t,)*)
+        where
+            $($t: FromStr, $t::Err: fmt::Display,)*
+        {
+            const ARITY: usize = $arity;
+            #[allow(unused_variables)]
+            fn from_fields(fields: &[Field<'_>]) -> Result<Self, ParseError> {
+                Ok(($(fields[$i].parse::<$t>()?,)*))
+            }
+        }
+    };
+}
+
+tuple_impl!(0;);
+tuple_impl!(1; A 0);
+tuple_impl!(2; A 0, B 1);
+tuple_impl!(3; A 0, B 1, C 2);
+tuple_impl!(4; A 0, B 1, C 2, D 3);
+tuple_impl!(5; A 0, B 1, C 2, D 3, E 4);
+tuple_impl!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+tuple_impl!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+tuple_impl!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+/// A compiled pattern.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Pattern {
+    /// The literal text around the fields. There is one more literal than
+    /// there are fields.
+    literals: Vec<String>,
+}
+
+impl Pattern {
+    /// # Panics
+    ///
+    /// Panics if the pattern has unmatched braces, or two fields with no
+    /// literal text between them.
+    pub fn new(pattern: &str) -> Pattern {
+        let mut literals = vec![String::new()];
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (c, chars.peek()) {
+                ('{', Some('{')) | ('}', Some('}')) => {
+                    chars.next();
+                    literals.last_mut().unwrap().push(c);
+                },
+                ('{', Some('}')) => {
+                    chars.next();
+                    if literals.len() > 1 && literals.last().unwrap().is_empty() {
+                        panic!("Pattern {:?} has two adjacent fields.", pattern);
+                    }
+                    literals.push(String::new());
+                },
+                ('{', _) | ('}', _) => panic!("Pattern {:?} has an unmatched brace.", pattern),
+                _ => literals.last_mut().unwrap().push(c),
+            }
+        }
+        Pattern { literals }
+    }
+
+    /// The number of fields in the pattern.
+    pub fn arity(&self) -> usize {
+        self.literals.len() - 1
+    }
+
+    /// Split the input into the fields of the pattern.
+    pub fn fields<'a>(&self, input: &'a str) -> Result<Vec<Field<'a>>, ParseError> {
+        // Positions are byte offsets, but columns count characters.
+        let column = |pos: usize| input[..pos].chars().count() + 1;
+        let expected = |pos: usize, lit: &str| ParseError {
+            column: column(pos),
+            kind: ParseErrorKind::ExpectedLiteral(lit.to_string()),
+        };
+
+        let first = &self.literals[0];
+        if !input.starts_with(first.as_str()) {
+            let matching = input.chars().zip(first.chars())
+                .take_while(|(a, b)| a == b)
+                .map(|(c, _)| c.len_utf8())
+                .sum();
+            return Err(expected(matching, first));
+        }
+        let mut pos = first.len();
+
+        let mut fields = Vec::with_capacity(self.arity());
+        for lit in &self.literals[1..] {
+            let end = if lit.is_empty() {
+                // Only the last literal can be empty.
+                input.len()
+            } else {
+                input[pos..].find(lit.as_str())
+                    .map(|idx| pos + idx)
+                    .ok_or_else(|| expected(pos, lit))?
+            };
+
+            let text = &input[pos..end];
+            let trimmed = text.trim_start();
+            fields.push(Field {
+                column: column(pos + (text.len() - trimmed.len())),
+                text: trimmed.trim_end(),
+            });
+            pos = end + lit.len();
+        }
+
+        if pos != input.len() {
+            return Err(ParseError {
+                column: column(pos),
+                kind: ParseErrorKind::TrailingInput,
+            });
+        }
+        Ok(fields)
+    }
+
+    /// Match the input against the pattern and parse the fields.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of fields in the pattern does not match `T`.
+    pub fn scan<T: FromFields>(&self, input: &str) -> Result<T, ParseError> {
+        assert_eq!(self.arity(), T::ARITY, "Pattern has the wrong number of fields.");
+        T::from_fields(&self.fields(input)?)
+    }
+}
+
+/// Match the input against the pattern and parse the fields.
+///
+/// # Panics
+///
+/// Panics if the pattern is invalid, or if its number of fields does not
+/// match `T`.
+pub fn scan<T: FromFields>(pattern: &str, input: &str) -> Result<T, ParseError> {
+    Pattern::new(pattern).scan(input)
+}
+
+/// Implement `FromStr` for a struct using a pattern. The fields of the
+/// pattern are assigned to the listed struct fields in order.
+///
+/// ```
+/// struct Claim {
+///     id: u32,
+///     x: u32,
+///     y: u32,
+/// }
+/// aoc_common::from_pattern!(Claim, "#{} @ {},{}", id, x, y);
+///
+/// let claim: Claim = "#1 @ 3,4".parse().unwrap();
+/// assert_eq!((claim.id, claim.x, claim.y), (1, 3, 4));
+/// ```
+#[macro_export]
+macro_rules! from_pattern {
+    ($ty:ident, $pattern:expr, $($field:ident),* $(,)?) => {
+        impl ::std::str::FromStr for $ty {
+            type Err = $crate::parse::ParseError;
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                let ($($field,)*) = $crate::parse::scan($pattern, s)?;
+                Ok($ty { $($field),* })
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields() {
+        let (id, x, y, w, h): (u32, u32, u32, u32, u32) =
+            scan("#{} @ {},{}: {}x{}", "#123 @ 3,2: 5x4").unwrap();
+        assert_eq!((id, x, y, w, h), (123, 3, 2, 5, 4));
+
+        let (card, rest): (u32, String) = scan("Card {}: {}", "Card   7: 1 2 | 3").unwrap();
+        assert_eq!((card, rest.as_str()), (7, "1 2 | 3"));
+
+        let (a, b): (char, char) = scan("{{{}}} -> {}", "{A} -> B").unwrap();
+        assert_eq!((a, b), ('A', 'B'));
+
+        let () = scan("nothing", "nothing").unwrap();
+    }
+
+    #[test]
+    fn errors() {
+        let err = scan::<(u32, u32)>("{}, {}", "12; 5").unwrap_err();
+        assert_eq!(err, ParseError {
+            column: 1,
+            kind: ParseErrorKind::ExpectedLiteral(", ".to_string()),
+        });
+
+        let err = scan::<(u32, u32)>("{}, {}", "12, x5").unwrap_err();
+        assert_eq!(err.column, 5);
+        assert_eq!(err.to_string(), "column 5: invalid value \"x5\": invalid digit found in string");
+
+        let err = scan::<(u32,)>("Game {}:", "Gaem 1:").unwrap_err();
+        assert_eq!(err.column, 3);
+
+        let err = scan::<(u32,)>("Game {}:", "Game 1: x").unwrap_err();
+        assert_eq!(err, ParseError {
+            column: 8,
+            kind: ParseErrorKind::TrailingInput,
+        });
+
+        // Columns count characters, not bytes.
+        assert_eq!(scan::<(u32,)>("Größe: {}", "Grösse: 1").unwrap_err().column, 4);
+        assert_eq!(scan::<(u32,)>("Größe: {}", "Größe: x").unwrap_err().column, 8);
+        assert_eq!(scan::<(u32,)>("→ {}", "→ 1 ←").unwrap_err().column, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn adjacent_fields() {
+        Pattern::new("{}{}");
+    }
+}
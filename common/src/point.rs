@@ -0,0 +1,287 @@
+//! Points and vectors in the plane, and the directions between them.
+//!
+//! The y axis points down, as in the puzzle inputs, so `Up` decreases y and
+//! points are ordered in reading order: by row, then by column.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+#[derive(Copy,Clone,Debug,Default,Eq,PartialEq,Hash)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// The difference between two points.
+pub type Vec2<T> = Point<T>;
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point { x, y }
+    }
+    pub fn map<U, F: FnMut(T) -> U>(self, mut f: F) -> Point<U> {
+        Point {
+            x: f(self.x),
+            y: f(self.y),
+        }
+    }
+}
+
+fn abs_diff<T: Ord + Sub<Output = T>>(a: T, b: T) -> T {
+    if a < b { b - a } else { a - b }
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> Point<T> {
+    pub fn manhattan(self, other: Point<T>) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+    /// The distance when diagonal moves are allowed.
+    pub fn chebyshev(self, other: Point<T>) -> T {
+        T::max(abs_diff(self.x, other.x), abs_diff(self.y, other.y))
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>> Point<T> {
+    pub fn euclidean_squared(self, other: Point<T>) -> T {
+        let d = self - other;
+        d.x * d.x + d.y * d.y
+    }
+}
+
+impl<T: Copy + From<i8> + Add<Output = T>> Point<T> {
+    /// The neighboring point in the given direction.
+    pub fn step<D: Into<Dir8>>(self, dir: D) -> Point<T> {
+        self + dir.into().delta()
+    }
+    pub fn neighbors4(self) -> impl Iterator<Item = Point<T>> {
+        Dir4::ALL.into_iter().map(move |dir| self.step(dir))
+    }
+    pub fn neighbors8(self) -> impl Iterator<Item = Point<T>> {
+        Dir8::ALL.into_iter().map(move |dir| self.step(dir))
+    }
+}
+
+impl<T: Ord> PartialOrd for Point<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for Point<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.y, &self.x).cmp(&(&other.y, &other.x))
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Point<T>;
+    fn add(self, other: Point<T>) -> Point<T> {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Point<T>;
+    fn sub(self, other: Point<T>) -> Point<T> {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Point<T>;
+    fn neg(self) -> Point<T> {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point<T> {
+    type Output = Point<T>;
+    fn mul(self, k: T) -> Point<T> {
+        Point::new(self.x * k, self.y * k)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point<T> {
+    fn add_assign(&mut self, other: Point<T>) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point<T> {
+    fn sub_assign(&mut self, other: Point<T>) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point::new(x, y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}, {}", self.x, self.y)
+    }
+}
+
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct ParsePointError {
+    pub text: String,
+}
+
+impl fmt::Display for ParsePointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected a point such as \"3, 4\", found {:?}", self.text)
+    }
+}
+
+impl std::error::Error for ParsePointError {}
+
+impl<T: FromStr> FromStr for Point<T> {
+    type Err = ParsePointError;
+
+    /// Parse `x, y` or `x,y`.
+    fn from_str(s: &str) -> Result<Self, ParsePointError> {
+        let invalid = || ParsePointError { text: s.to_string() };
+        let (x, y) = s.split_once(',').ok_or_else(invalid)?;
+        Ok(Point {
+            x: x.trim().parse().map_err(|_| invalid())?,
+            y: y.trim().parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// The four directions along the axes.
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir4 {
+    /// Every direction, clockwise from `Up`.
+    pub const ALL: [Dir4; 4] = [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+    pub fn turn_right(self) -> Dir4 {
+        Dir4::ALL[(self as usize + 1) % 4]
+    }
+    pub fn turn_left(self) -> Dir4 {
+        Dir4::ALL[(self as usize + 3) % 4]
+    }
+    pub fn opposite(self) -> Dir4 {
+        Dir4::ALL[(self as usize + 2) % 4]
+    }
+    pub fn delta<T: From<i8>>(self) -> Vec2<T> {
+        Dir8::from(self).delta()
+    }
+    /// Parse one of `U`, `R`, `D`, `L` or `^`, `>`, `v`, `<`.
+    pub fn from_char(c: char) -> Option<Dir4> {
+        match c {
+            'U' | '^' => Some(Dir4::Up),
+            'R' | '>' => Some(Dir4::Right),
+            'D' | 'v' => Some(Dir4::Down),
+            'L' | '<' => Some(Dir4::Left),
+            _ => None,
+        }
+    }
+}
+
+/// The four directions along the axes and the four diagonals.
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+pub enum Dir8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Dir8 {
+    /// Every direction, clockwise from `Up`.
+    pub const ALL: [Dir8; 8] = [
+        Dir8::Up, Dir8::UpRight, Dir8::Right, Dir8::DownRight,
+        Dir8::Down, Dir8::DownLeft, Dir8::Left, Dir8::UpLeft,
+    ];
+
+    /// Turn 45 degrees clockwise.
+    pub fn turn_right(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 1) % 8]
+    }
+    /// Turn 45 degrees counterclockwise.
+    pub fn turn_left(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 7) % 8]
+    }
+    pub fn opposite(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 4) % 8]
+    }
+    pub fn delta<T: From<i8>>(self) -> Vec2<T> {
+        let (x, y) = match self {
+            Dir8::Up => (0, -1),
+            Dir8::UpRight => (1, -1),
+            Dir8::Right => (1, 0),
+            Dir8::DownRight => (1, 1),
+            Dir8::Down => (0, 1),
+            Dir8::DownLeft => (-1, 1),
+            Dir8::Left => (-1, 0),
+            Dir8::UpLeft => (-1, -1),
+        };
+        Point::new(T::from(x), T::from(y))
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Dir8 {
+        Dir8::ALL[2 * dir as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_and_distances() {
+        let a = Point::new(1, 2);
+        let b: Point<i32> = "4, -2".parse().unwrap();
+        assert_eq!(a + b, Point::new(5, 0));
+        assert_eq!(b - a, Point::new(3, -4));
+        assert_eq!(-a * 2, Point::new(-2, -4));
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(a.euclidean_squared(b), 25);
+        assert_eq!(Point::new(3u32, 1).manhattan(Point::new(1, 4)), 5);
+        assert!("4 -2".parse::<Point<i32>>().is_err());
+    }
+
+    #[test]
+    fn reading_order() {
+        let mut points = vec![Point::new(2, 1), Point::new(5, 0), Point::new(0, 1)];
+        points.sort();
+        assert_eq!(points, vec![Point::new(5, 0), Point::new(0, 1), Point::new(2, 1)]);
+    }
+
+    #[test]
+    fn directions() {
+        assert_eq!(Dir4::Up.turn_right(), Dir4::Right);
+        assert_eq!(Dir4::Up.turn_left(), Dir4::Left);
+        assert_eq!(Dir4::Left.opposite(), Dir4::Right);
+        assert_eq!(Dir8::UpLeft.turn_right(), Dir8::Up);
+        assert_eq!(Dir8::DownRight.opposite(), Dir8::UpLeft);
+        assert_eq!(Dir8::from(Dir4::Down), Dir8::Down);
+        assert_eq!(Point::new(0i64, 0).step(Dir4::Up), Point::new(0, -1));
+        for dir in Dir8::ALL {
+            assert_eq!(dir.delta::<i32>() + dir.opposite().delta(), Point::new(0, 0));
+        }
+        assert_eq!(Point::new(0, 0).neighbors8().filter(|p: &Point<i32>| p.y == 1).count(), 3);
+    }
+}
@@ -1,3 +1,4 @@
+#[derive(Default)]
 pub struct RangeMapBuilder {
     mappings: Vec<RangeMapPart>,
 }
@@ -18,6 +19,54 @@ pub struct Range {
     pub len: u64,
 }
 
+impl Range {
+    /// The first value after the range.
+    pub fn end(&self) -> u64 {
+        self.from + self.len
+    }
+
+    pub fn intersect(&self, other: &Range) -> Option<Range> {
+        let from = u64::max(self.from, other.from);
+        let end = u64::min(self.end(), other.end());
+        if from < end {
+            Some(Range { from, len: end - from })
+        } else {
+            None
+        }
+    }
+}
+
+/// Sort the ranges and merge the ones that overlap or touch. Empty ranges
+/// are removed.
+pub fn normalize(mut ranges: Vec<Range>) -> Vec<Range> {
+    ranges.retain(|r| r.len > 0);
+    ranges.sort_unstable_by_key(|r| r.from);
+
+    let mut output: Vec<Range> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match output.last_mut() {
+            Some(last) if range.from <= last.end() => {
+                last.len = u64::max(last.end(), range.end()) - last.from;
+            },
+            _ => output.push(range),
+        }
+    }
+    output
+}
+
+/// The values that the chain of maps sends into `range`, where every map
+/// leaves values outside its ranges unchanged.
+pub fn preimage_chain(maps: &[RangeMap], range: Range) -> Vec<Range> {
+    let mut ranges = vec![range];
+    for map in maps.iter().rev() {
+        let preimages = ranges.into_iter()
+            .flat_map(|r| map.preimage(r))
+            .collect();
+        ranges = normalize(preimages);
+    }
+    ranges
+}
+
 impl RangeMapBuilder {
     pub fn new() -> Self {
         Self {
@@ -94,7 +143,10 @@ impl RangeMap {
 
             let range = &self.mappings[range_idx];
             if remaining_range.from < range.from {
-                let diff = range.from - remaining_range.from;
+                let diff = u64::min(
+                    range.from - remaining_range.from,
+                    remaining_range.len,
+                );
                 output.push((
                     Range {
                         from: remaining_range.from,
@@ -129,6 +181,28 @@ impl RangeMap {
 
         output
     }
+
+    /// The values that are sent into `range`, where values outside the
+    /// ranges of the map are left unchanged. The result is normalized.
+    pub fn preimage(&self, range: Range) -> Vec<Range> {
+        let mut output: Vec<Range> = self.map_range(range.clone())
+            .into_iter()
+            .filter(|(_, mapped)| mapped.is_none())
+            .map(|(unmapped, _)| unmapped)
+            .collect();
+
+        for part in &self.mappings {
+            let image = Range { from: part.to, len: part.len };
+            if let Some(hit) = image.intersect(&range) {
+                output.push(Range {
+                    from: part.from + (hit.from - part.to),
+                    len: hit.len,
+                });
+            }
+        }
+
+        normalize(output)
+    }
 }
 
 #[cfg(test)]
@@ -171,5 +245,47 @@ mod tests {
                 (Range { from: 5, len: 2 }, Some(Range { from: 2, len: 2 })),
             ]
         );
+        assert_eq!(map.map_range(Range { from: 2, len: 2 }),
+            vec![(Range { from: 2, len: 2 }, None)]
+        );
+    }
+
+    #[test]
+    fn preimage() {
+        let mut map = RangeMapBuilder::new();
+        map.add_range(0, 7, 2);
+        map.add_range(5, 2, 2);
+        let map = map.build();
+
+        // 2 and 3 are reached from 5 and 6, and 7 is reached from 0.
+        assert_eq!(map.preimage(Range { from: 2, len: 6 }),
+            vec![Range { from: 0, len: 1 }, Range { from: 2, len: 6 }]
+        );
+        assert_eq!(map.preimage(Range { from: 0, len: 2 }), vec![]);
+        assert_eq!(map.preimage(Range { from: 8, len: 1 }),
+            vec![Range { from: 1, len: 1 }, Range { from: 8, len: 1 }]
+        );
+    }
+
+    #[test]
+    fn preimage_of_chain() {
+        let mut first = RangeMapBuilder::new();
+        first.add_range(10, 0, 5);
+        let mut second = RangeMapBuilder::new();
+        second.add_range(0, 100, 3);
+        let maps = [first.build(), second.build()];
+
+        for x in 0..20 {
+            let y = maps.iter().fold(x, |x, map| map.get(x).unwrap_or(x));
+            let pre = preimage_chain(&maps, Range { from: y, len: 1 });
+            assert!(pre.iter().any(|r| r.from <= x && x < r.end()));
+        }
+        assert_eq!(preimage_chain(&maps, Range { from: 100, len: 3 }),
+            vec![
+                Range { from: 0, len: 3 },
+                Range { from: 10, len: 3 },
+                Range { from: 100, len: 3 },
+            ]
+        );
     }
 }
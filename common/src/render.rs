@@ -0,0 +1,417 @@
+//! Drawing grids and point sets, to the terminal or to image files.
+//!
+//! A frame is a [`Grid`] of colors. It can be printed with ANSI escape
+//! codes, two rows per line of text, or encoded as a PPM or PNG image. The
+//! PNG encoder stores the pixels without compression, which keeps it small
+//! and free of dependencies at the cost of larger files.
+
+use crate::grid::Grid;
+use crate::point::Point;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Copy,Clone,Debug,Default,Eq,PartialEq,Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const GRAY: Rgb = Rgb(128, 128, 128);
+
+    /// A color for the `i`th of many categories. Hues are spread by the
+    /// golden angle, so nearby indexes get clearly different colors.
+    pub fn palette(i: usize) -> Rgb {
+        let hue = (i as f64 * 137.507_764) % 360.0;
+        Rgb::from_hsv(hue, 0.65, 0.95)
+    }
+    /// A color from hue in degrees, and saturation and value between zero
+    /// and one.
+    pub fn from_hsv(hue: f64, saturation: f64, value: f64) -> Rgb {
+        let c = value * saturation;
+        let h = hue.rem_euclid(360.0) / 60.0;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = value - c;
+        let byte = |v: f64| ((v + m) * 255.0).round() as u8;
+        Rgb(byte(r), byte(g), byte(b))
+    }
+    /// Mix with `other`, where `t` is zero for `self` and one for `other`.
+    pub fn lerp(self, other: Rgb, t: f64) -> Rgb {
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Rgb(mix(self.0, other.0), mix(self.1, other.1), mix(self.2, other.2))
+    }
+}
+
+/// A frame showing the given points, just large enough to hold all of
+/// them. Returns the frame and the position of its top left pixel, or
+/// `None` if there are no points, since a frame can't be empty.
+pub fn plot<I>(points: I, background: Rgb) -> Option<(Grid<Rgb>, Point<i64>)>
+where
+    I: IntoIterator<Item = (Point<i64>, Rgb)>,
+{
+    let points: Vec<_> = points.into_iter().collect();
+    if points.is_empty() {
+        return None;
+    }
+    let min_x = points.iter().map(|(p, _)| p.x).min().unwrap();
+    let min_y = points.iter().map(|(p, _)| p.y).min().unwrap();
+    let max_x = points.iter().map(|(p, _)| p.x).max().unwrap();
+    let max_y = points.iter().map(|(p, _)| p.y).max().unwrap();
+    let mut frame = Grid::new((max_x - min_x + 1) as usize, (max_y - min_y + 1) as usize, background);
+    for (p, color) in points {
+        frame[Point::new((p.x - min_x) as usize, (p.y - min_y) as usize)] = color;
+    }
+    Some((frame, Point::new(min_x, min_y)))
+}
+
+/// Every pixel of the frame as a `factor` by `factor` square.
+///
+/// # Panics
+///
+/// Panics if `factor` is zero.
+pub fn scale(frame: &Grid<Rgb>, factor: usize) -> Grid<Rgb> {
+    assert!(factor > 0, "Cannot scale a frame by zero.");
+    let mut scaled = Grid::new(frame.width() * factor, frame.height() * factor, Rgb::BLACK);
+    for pos in scaled.positions() {
+        scaled[pos] = frame[Point::new(pos.x / factor, pos.y / factor)];
+    }
+    scaled
+}
+
+/// The frame as text with 24-bit ANSI colors. Every character is a half
+/// block showing two pixels above each other, so pixels come out roughly
+/// square.
+pub fn to_ansi(frame: &Grid<Rgb>) -> String {
+    let mut text = String::new();
+    for y in (0..frame.height()).step_by(2) {
+        for x in 0..frame.width() {
+            let Rgb(r, g, b) = frame[Point::new(x, y)];
+            write!(text, "\x1b[38;2;{};{};{}m", r, g, b).unwrap();
+            if y + 1 < frame.height() {
+                let Rgb(r, g, b) = frame[Point::new(x, y + 1)];
+                write!(text, "\x1b[48;2;{};{};{}m", r, g, b).unwrap();
+            }
+            text.push('▀');
+        }
+        text.push_str("\x1b[0m\n");
+    }
+    text
+}
+
+/// An error unless the frame has at least one pixel, which image formats
+/// require.
+fn check_size(frame: &Grid<Rgb>) -> io::Result<()> {
+    if frame.width() == 0 || frame.height() == 0 {
+        let msg = format!("cannot write a {}x{} frame", frame.width(), frame.height());
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+    }
+    Ok(())
+}
+
+/// The frame as a binary PPM image.
+///
+/// # Panics
+///
+/// Panics if the frame is empty.
+pub fn to_ppm(frame: &Grid<Rgb>) -> Vec<u8> {
+    check_size(frame).unwrap();
+    let mut ppm = format!("P6\n{} {}\n255\n", frame.width(), frame.height()).into_bytes();
+    for pos in frame.positions() {
+        let Rgb(r, g, b) = frame[pos];
+        ppm.extend_from_slice(&[r, g, b]);
+    }
+    ppm
+}
+
+/// The frame as an uncompressed PNG image.
+///
+/// # Panics
+///
+/// Panics if the frame is empty.
+pub fn to_png(frame: &Grid<Rgb>) -> Vec<u8> {
+    check_size(frame).unwrap();
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(frame.width() as u32).to_be_bytes());
+    header.extend_from_slice(&(frame.height() as u32).to_be_bytes());
+    // 8 bits per channel, truecolor, and the only compression, filter and
+    // interlace methods there are.
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+    png_chunk(&mut png, b"IHDR", &header);
+
+    // Every row starts with its filter type, which is zero for none.
+    let mut raw = Vec::with_capacity(frame.height() * (1 + 3 * frame.width()));
+    for y in 0..frame.height() {
+        raw.push(0);
+        for &Rgb(r, g, b) in frame.row(y) {
+            raw.extend_from_slice(&[r, g, b]);
+        }
+    }
+    png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+    png_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// A zlib stream holding `data` in uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = 0xffff;
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        // An empty final block.
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(last as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    // The sums stay below 2^32 for this many bytes before they must be
+    // reduced.
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+}
+
+impl ImageFormat {
+    /// The format matching the extension of `path`, if it is `ppm` or `png`.
+    pub fn from_path(path: &Path) -> Option<ImageFormat> {
+        match path.extension()?.to_str()? {
+            "ppm" => Some(ImageFormat::Ppm),
+            "png" => Some(ImageFormat::Png),
+            _ => None,
+        }
+    }
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Png => "png",
+        }
+    }
+    pub fn encode(self, frame: &Grid<Rgb>) -> Vec<u8> {
+        match self {
+            ImageFormat::Ppm => to_ppm(frame),
+            ImageFormat::Png => to_png(frame),
+        }
+    }
+}
+
+/// Write a frame to `path`, as an image if the extension is `ppm` or `png`
+/// and as ANSI text otherwise. Fails if the frame is empty.
+pub fn write(path: &Path, frame: &Grid<Rgb>) -> io::Result<()> {
+    check_size(frame)?;
+    match ImageFormat::from_path(path) {
+        Some(format) => std::fs::write(path, format.encode(frame)),
+        None => std::fs::write(path, to_ansi(frame)),
+    }
+}
+
+#[derive(Clone,Debug)]
+enum Target {
+    Files {
+        dir: PathBuf,
+        prefix: String,
+        format: ImageFormat,
+    },
+    Terminal {
+        delay: Duration,
+    },
+}
+
+/// Frames of an animation, written as they are produced.
+#[derive(Clone,Debug)]
+pub struct FrameSequence {
+    target: Target,
+    scale: usize,
+    frames: usize,
+}
+
+impl FrameSequence {
+    /// Write every frame to its own numbered file in `dir`, such as
+    /// `prefix_0007.png`, creating the directory if needed.
+    pub fn files(dir: &Path, prefix: &str, format: ImageFormat) -> io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        Ok(FrameSequence {
+            target: Target::Files {
+                dir: dir.to_path_buf(),
+                prefix: prefix.to_string(),
+                format,
+            },
+            scale: 1,
+            frames: 0,
+        })
+    }
+    /// Draw every frame over the previous one in the terminal, and wait for
+    /// `delay` after each.
+    pub fn terminal(delay: Duration) -> Self {
+        FrameSequence {
+            target: Target::Terminal { delay },
+            scale: 1,
+            frames: 0,
+        }
+    }
+    /// Draw every pixel as a `factor` by `factor` square.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is zero.
+    pub fn scale(mut self, factor: usize) -> Self {
+        assert!(factor > 0, "Cannot scale frames by zero.");
+        self.scale = factor;
+        self
+    }
+    /// The number of frames written so far.
+    pub fn len(&self) -> usize {
+        self.frames
+    }
+    pub fn is_empty(&self) -> bool {
+        self.frames == 0
+    }
+
+    /// Write the next frame. Fails if the frame is empty.
+    pub fn push(&mut self, frame: &Grid<Rgb>) -> io::Result<()> {
+        check_size(frame)?;
+        let scaled;
+        let frame = if self.scale == 1 {
+            frame
+        } else {
+            scaled = scale(frame, self.scale);
+            &scaled
+        };
+        match &self.target {
+            Target::Files { dir, prefix, format } => {
+                let name = format!("{}_{:04}.{}", prefix, self.frames, format.extension());
+                std::fs::write(dir.join(name), format.encode(frame))?;
+            },
+            Target::Terminal { delay } => {
+                let mut stdout = io::stdout().lock();
+                // Move the cursor home and clear the screen.
+                write!(stdout, "\x1b[H\x1b[2J{}", to_ansi(frame))?;
+                stdout.flush()?;
+                thread::sleep(*delay);
+            },
+        }
+        self.frames += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        assert_eq!(adler32(&[0xff; 100_000]), {
+            let (a, b) = (0..100_000u64).fold((1u64, 0u64), |(a, b), _| (a + 255, b + a + 255));
+            (((b % 65521) << 16) | (a % 65521)) as u32
+        });
+    }
+
+    #[test]
+    fn images() {
+        let (frame, origin) = plot([(Point::new(-1, 2), Rgb::WHITE), (Point::new(1, 3), Rgb(1, 2, 3))], Rgb::BLACK).unwrap();
+        assert_eq!((frame.width(), frame.height(), origin), (3, 2, Point::new(-1, 2)));
+        assert_eq!(frame.row(1), [Rgb::BLACK, Rgb::BLACK, Rgb(1, 2, 3)]);
+
+        let ppm = to_ppm(&frame);
+        assert!(ppm.starts_with(b"P6\n3 2\n255\n\xff\xff\xff\0\0\0"));
+        assert_eq!(ppm.len(), 11 + 18);
+
+        let png = to_png(&frame);
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x03\0\0\0\x02\x08\x02"));
+        assert!(png.ends_with(b"\0\0\0\0IEND\xae\x42\x60\x82"));
+        // Two rows of a filter byte and three pixels in one stored block.
+        let raw_len = 2 * (1 + 9);
+        let idat = &png[33..];
+        assert_eq!(&idat[..8], [&((2 + 5 + raw_len + 4) as u32).to_be_bytes()[..], b"IDAT"].concat());
+        assert_eq!(&idat[8..15], [0x78, 0x01, 1, raw_len as u8, 0, !(raw_len as u8), 0xff]);
+
+        assert_eq!(zlib_stored(&[0; 70_000]).len(), 2 + 2 * 5 + 70_000 + 4);
+        assert_eq!(scale(&frame, 2).row(3)[5], Rgb(1, 2, 3));
+    }
+
+    #[test]
+    fn empty_frames() {
+        assert!(plot([], Rgb::BLACK).is_none());
+        let empty = Grid::new(0, 3, Rgb::BLACK);
+        let path = std::env::temp_dir().join("aoc_common_empty_frame.png");
+        assert_eq!(write(&path, &empty).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        assert!(!path.exists());
+        let mut frames = FrameSequence::terminal(Duration::ZERO);
+        assert!(frames.push(&empty).is_err());
+        assert!(frames.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot scale frames by zero.")]
+    fn zero_scale() {
+        FrameSequence::terminal(Duration::ZERO).scale(0);
+    }
+
+    #[test]
+    fn ansi_and_colors() {
+        let frame = Grid::new(1, 3, Rgb(1, 2, 3));
+        assert_eq!(to_ansi(&frame), "\x1b[38;2;1;2;3m\x1b[48;2;1;2;3m▀\x1b[0m\n\x1b[38;2;1;2;3m▀\x1b[0m\n");
+        assert_eq!(Rgb::from_hsv(0.0, 1.0, 1.0), Rgb(255, 0, 0));
+        assert_eq!(Rgb::from_hsv(240.0, 1.0, 1.0), Rgb(0, 0, 255));
+        assert_ne!(Rgb::palette(0), Rgb::palette(1));
+        assert_eq!(Rgb::BLACK.lerp(Rgb::WHITE, 0.5), Rgb::GRAY);
+    }
+}
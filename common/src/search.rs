@@ -0,0 +1,348 @@
+//! Shortest paths over any state type.
+//!
+//! States are explored through a closure returning the neighbors of a
+//! state, so the graph never has to be built up front. Every search records
+//! all predecessors on shortest paths, which gives both a single path and,
+//! when there are ties, every shortest path.
+//!
+//! With several start states, each state is also labelled with the start it
+//! is closest to, or as a tie if several starts are equally close.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The start state closest to a state.
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+pub enum Owner {
+    /// The index of the start state among the starts of the search.
+    Start(usize),
+    /// Several starts are equally close.
+    Tie,
+}
+
+impl Owner {
+    fn merge(self, other: Owner) -> Owner {
+        if self == other { self } else { Owner::Tie }
+    }
+}
+
+#[derive(Clone,Debug)]
+struct Node<S, C> {
+    dist: C,
+    preds: Vec<S>,
+    owner: Owner,
+}
+
+/// The result of a search from one or more starts.
+#[derive(Clone,Debug)]
+pub struct Search<S, C> {
+    nodes: HashMap<S, Node<S, C>>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy + Ord> Search<S, C> {
+    fn new() -> Self {
+        Search {
+            nodes: HashMap::new(),
+        }
+    }
+
+    /// Add a start state. Returns `false` if it was already added.
+    fn start(&mut self, state: S, index: usize, zero: C) -> bool {
+        if self.nodes.contains_key(&state) {
+            return false;
+        }
+        self.nodes.insert(state, Node {
+            dist: zero,
+            preds: Vec::new(),
+            owner: Owner::Start(index),
+        });
+        true
+    }
+
+    /// Record an edge to `to` with the given total distance. Returns `true`
+    /// if `to` got a new, shorter distance and must be explored.
+    fn relax(&mut self, from: &S, to: S, dist: C) -> bool {
+        let owner = self.nodes[from].owner;
+        match self.nodes.get_mut(&to) {
+            None => {
+                self.nodes.insert(to, Node {
+                    dist,
+                    preds: vec![from.clone()],
+                    owner,
+                });
+                true
+            },
+            Some(node) if dist < node.dist => {
+                *node = Node {
+                    dist,
+                    preds: vec![from.clone()],
+                    owner,
+                };
+                true
+            },
+            Some(node) if dist == node.dist && !node.preds.contains(from) => {
+                node.preds.push(from.clone());
+                node.owner = node.owner.merge(owner);
+                false
+            },
+            Some(_) => false,
+        }
+    }
+
+    /// The number of states that were reached.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+    pub fn distance(&self, state: &S) -> Option<C> {
+        self.nodes.get(state).map(|node| node.dist)
+    }
+    /// Every reached state with its distance, in no particular order.
+    pub fn distances(&self) -> impl Iterator<Item = (&S, C)> {
+        self.nodes.iter().map(|(state, node)| (state, node.dist))
+    }
+    pub fn owner(&self, state: &S) -> Option<Owner> {
+        self.nodes.get(state).map(|node| node.owner)
+    }
+    /// Every reached state with its closest start.
+    pub fn owners(&self) -> impl Iterator<Item = (&S, Owner)> {
+        self.nodes.iter().map(|(state, node)| (state, node.owner))
+    }
+    /// The states directly before `state` on its shortest paths. Empty for
+    /// the starts and for states that were not reached.
+    pub fn predecessors(&self, state: &S) -> &[S] {
+        self.nodes.get(state).map_or(&[], |node| &node.preds)
+    }
+
+    /// A shortest path from a start to `goal`, including both.
+    pub fn path_to(&self, goal: &S) -> Option<Vec<S>> {
+        self.nodes.get(goal)?;
+        let mut path = vec![goal.clone()];
+        while let Some(pred) = self.predecessors(path.last().unwrap()).first() {
+            path.push(pred.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every shortest path from a start to `goal`. The number of paths can
+    /// grow exponentially with their length.
+    pub fn all_paths_to(&self, goal: &S) -> Vec<Vec<S>> {
+        if !self.nodes.contains_key(goal) {
+            return Vec::new();
+        }
+        let mut paths = Vec::new();
+        let mut stack = vec![vec![goal.clone()]];
+        while let Some(path) = stack.pop() {
+            let preds = self.predecessors(path.last().unwrap());
+            if preds.is_empty() {
+                paths.push(path.into_iter().rev().collect());
+                continue;
+            }
+            for pred in preds {
+                let mut longer = path.clone();
+                longer.push(pred.clone());
+                stack.push(longer);
+            }
+        }
+        paths
+    }
+}
+
+/// Breadth-first search where every edge has length one.
+pub fn bfs<S, F, I>(start: S, neighbors: F) -> Search<S, usize>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    bfs_multi([start], neighbors)
+}
+
+/// Breadth-first search from several starts at once.
+pub fn bfs_multi<S, F, I>(starts: impl IntoIterator<Item = S>, mut neighbors: F) -> Search<S, usize>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+    for (i, start) in starts.into_iter().enumerate() {
+        if search.start(start.clone(), i, 0) {
+            queue.push_back(start);
+        }
+    }
+    while let Some(state) = queue.pop_front() {
+        let dist = search.nodes[&state].dist + 1;
+        for next in neighbors(&state) {
+            if search.relax(&state, next.clone(), dist) {
+                queue.push_back(next);
+            }
+        }
+    }
+    search
+}
+
+/// Dijkstra's algorithm from one or more starts. The neighbor closure gives
+/// each neighbor with the cost of the edge to it, and costs must be
+/// positive for ties to be recorded correctly.
+pub fn dijkstra<S, C, F, I>(starts: impl IntoIterator<Item = S>, mut neighbors: F) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut search = Search::new();
+    // The heap refers to states by their index in `pushed`, so states don't
+    // need to be ordered.
+    let mut pushed = Vec::new();
+    let mut heap = BinaryHeap::new();
+    for (i, start) in starts.into_iter().enumerate() {
+        if search.start(start.clone(), i, C::default()) {
+            heap.push(Reverse((C::default(), pushed.len())));
+            pushed.push(start);
+        }
+    }
+    while let Some(Reverse((dist, idx))) = heap.pop() {
+        let state = pushed[idx].clone();
+        if search.nodes[&state].dist < dist {
+            continue;
+        }
+        for (next, cost) in neighbors(&state) {
+            let next_dist = dist + cost;
+            if search.relax(&state, next.clone(), next_dist) {
+                heap.push(Reverse((next_dist, pushed.len())));
+                pushed.push(next);
+            }
+        }
+    }
+    search
+}
+
+/// A* search for the shortest path to a state satisfying `is_goal`. The
+/// heuristic must never overestimate the remaining cost. Returns the cost
+/// and the path, including the start and the goal.
+pub fn astar<S, C, F, I, G, H>(
+    start: S,
+    mut is_goal: G,
+    mut neighbors: F,
+    mut heuristic: H,
+) -> Option<(C, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+    H: FnMut(&S) -> C,
+{
+    let mut search = Search::new();
+    let mut pushed = vec![start.clone()];
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((heuristic(&start), C::default(), 0)));
+    search.start(start, 0, C::default());
+    while let Some(Reverse((_, dist, idx))) = heap.pop() {
+        let state = pushed[idx].clone();
+        if search.nodes[&state].dist < dist {
+            continue;
+        }
+        if is_goal(&state) {
+            return Some((dist, search.path_to(&state).unwrap()));
+        }
+        for (next, cost) in neighbors(&state) {
+            let next_dist = dist + cost;
+            if search.relax(&state, next.clone(), next_dist) {
+                heap.push(Reverse((next_dist + heuristic(&next), next_dist, pushed.len())));
+                pushed.push(next);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The open cells of a small maze as `(x, y)`.
+    fn maze_neighbors(maze: &'static [&'static str]) -> impl FnMut(&(i32, i32)) -> Vec<(i32, i32)> {
+        move |&(x, y)| {
+            [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)].into_iter()
+                .filter(|&(x, y)| {
+                    let row = maze.get(y as usize).map_or(&b""[..], |row| row.as_bytes());
+                    x >= 0 && y >= 0 && row.get(x as usize) == Some(&b'.')
+                })
+                .collect()
+        }
+    }
+
+    const MAZE: &[&str] = &[
+        "...#",
+        ".#..",
+        "....",
+    ];
+
+    #[test]
+    fn bfs_paths() {
+        let search = bfs((0, 0), maze_neighbors(MAZE));
+        assert_eq!(search.distance(&(3, 1)), Some(4));
+        assert_eq!(search.distance(&(3, 0)), None);
+        assert_eq!(search.path_to(&(3, 1)).unwrap().len(), 5);
+
+        let mut paths = search.all_paths_to(&(2, 2));
+        paths.sort();
+        assert_eq!(paths, vec![
+            vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)],
+            vec![(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)],
+        ]);
+        assert_eq!(search.predecessors(&(2, 2)).len(), 2);
+    }
+
+    #[test]
+    fn multi_source_owners() {
+        let search = bfs_multi([(0, 0), (3, 1)], maze_neighbors(MAZE));
+        assert_eq!(search.owner(&(0, 2)), Some(Owner::Start(0)));
+        assert_eq!(search.owner(&(2, 2)), Some(Owner::Start(1)));
+        // Both are two steps from each start.
+        assert_eq!(search.owner(&(2, 0)), Some(Owner::Tie));
+        assert_eq!(search.owner(&(1, 2)), Some(Owner::Tie));
+        assert_eq!(search.path_to(&(2, 2)), Some(vec![(3, 1), (2, 1), (2, 2)]));
+    }
+
+    #[test]
+    fn weighted() {
+        // A direct edge that is longer than going around, and two equally
+        // long paths to 3.
+        let edges = |&n: &u32| -> Vec<(u32, u64)> {
+            match n {
+                0 => vec![(1, 10), (2, 3)],
+                2 => vec![(1, 4), (3, 6)],
+                1 => vec![(3, 2)],
+                _ => vec![],
+            }
+        };
+        let search = dijkstra([0], edges);
+        assert_eq!(search.distance(&1), Some(7));
+        assert_eq!(search.distance(&3), Some(9));
+        assert_eq!(search.path_to(&1), Some(vec![0, 2, 1]));
+        assert_eq!(search.all_paths_to(&3).len(), 2);
+
+        assert_eq!(astar(0, |&n| n == 3, edges, |_| 0), Some((9, vec![0, 2, 3])));
+        assert_eq!(astar(0, |&n| n == 4, edges, |_| 0), None);
+    }
+
+    #[test]
+    fn astar_grid() {
+        let goal = (3, 1);
+        let found = astar((0, 0), |&p| p == goal,
+            |p| maze_neighbors(MAZE)(p).into_iter().map(|n| (n, 1u32)),
+            |&(x, y)| (goal.0 - x).unsigned_abs() + (goal.1 - y).unsigned_abs());
+        assert_eq!(found.map(|(cost, path)| (cost, path.len())), Some((4, 5)));
+    }
+}
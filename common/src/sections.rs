@@ -0,0 +1,69 @@
+//! Splitting inputs into blocks separated by blank lines.
+//!
+//! Lines may end in `\n` or `\r\n`, and trailing whitespace is ignored, so a
+//! line containing only spaces counts as blank.
+
+/// A block of lines whose first line is a title, such as
+/// `seed-to-soil map:`.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Section<'a> {
+    pub header: &'a str,
+    pub lines: Vec<&'a str>,
+}
+
+impl<'a> Section<'a> {
+    /// The header without a trailing colon.
+    pub fn title(&self) -> &'a str {
+        self.header.strip_suffix(':').unwrap_or(self.header)
+    }
+}
+
+/// Split the input into blocks of non-blank lines. Several blank lines in a
+/// row count as one separator, and blank lines at the start or end of the
+/// input are ignored.
+pub fn paragraphs(input: &str) -> Vec<Vec<&str>> {
+    let mut paragraphs = Vec::new();
+    let mut curr = Vec::new();
+    for line in input.lines().map(str::trim_end) {
+        if line.is_empty() {
+            if !curr.is_empty() {
+                paragraphs.push(std::mem::take(&mut curr));
+            }
+        } else {
+            curr.push(line);
+        }
+    }
+    if !curr.is_empty() {
+        paragraphs.push(curr);
+    }
+    paragraphs
+}
+
+/// Split the input into blocks, using the first line of each block as its
+/// header.
+pub fn sections(input: &str) -> Vec<Section<'_>> {
+    paragraphs(input)
+        .into_iter()
+        .map(|mut lines| {
+            let header = lines.remove(0);
+            Section { header, lines }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split() {
+        let input = "seeds: 1 2\r\n\r\nfirst map:\r\n1 2 3 \r\n4 5 6\r\n  \r\n\nsecond map:\n7 8 9\n\n";
+        assert_eq!(sections(input), vec![
+            Section { header: "seeds: 1 2", lines: vec![] },
+            Section { header: "first map:", lines: vec!["1 2 3", "4 5 6"] },
+            Section { header: "second map:", lines: vec!["7 8 9"] },
+        ]);
+        assert_eq!(sections(input)[1].title(), "first map");
+        assert_eq!(paragraphs("\n\n"), Vec::<Vec<&str>>::new());
+    }
+}
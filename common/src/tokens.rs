@@ -0,0 +1,195 @@
+//! Helpers for parsing whitespace or comma separated tokens.
+//!
+//! All of these skip empty tokens rather than reporting them, so splitting
+//! `"1  2"` on spaces gives two numbers, as does the `"1,2,"` of a line with
+//! a trailing comma. Token indexes in errors count the skipped tokens too,
+//! so they match the position in the original split.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum TokenError {
+    /// The token at this index could not be parsed.
+    InvalidToken {
+        index: usize,
+        text: String,
+        message: String,
+    },
+    /// There were not exactly `expected` tokens.
+    WrongCount {
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TokenError::InvalidToken { index, text, message } => {
+                write!(f, "token {} ({:?}): {}", index, text, message)
+            },
+            TokenError::WrongCount { expected, found } => {
+                write!(f, "expected {} tokens, found {}", expected, found)
+            },
+        }
+    }
+}
+
+impl std::error::Error for TokenError {}
+
+/// Parse every non-empty token, panicking with the index of the first
+/// invalid one.
+pub fn parse_iter<'a, T>(iter: impl Iterator<Item = &'a str>) -> Vec<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    match try_parse_iter(iter) {
+        Ok(values) => values,
+        Err(err) => panic!("{}", err),
+    }
+}
+
+/// Like `try_parse_iter`, but parses the tokens as they are consumed.
+/// Parsing continues after an invalid token.
+pub fn parse_iter_lazy<'a, T>(
+    iter: impl Iterator<Item = &'a str> + 'a,
+) -> impl Iterator<Item = Result<T, TokenError>> + 'a
+where
+    T: FromStr + 'a,
+    T::Err: fmt::Display,
+{
+    iter.enumerate()
+        .filter(|(_, s)| !s.is_empty())
+        .map(|(index, s)| parse_token(index, s))
+}
+
+fn parse_token<T>(index: usize, s: &str) -> Result<T, TokenError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    s.parse().map_err(|err: T::Err| TokenError::InvalidToken {
+        index,
+        text: s.to_string(),
+        message: err.to_string(),
+    })
+}
+
+/// Parse every non-empty token, returning the first invalid token as an
+/// error.
+pub fn try_parse_iter<'a, T>(iter: impl Iterator<Item = &'a str>) -> Result<Vec<T>, TokenError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    iter.enumerate()
+        .filter(|(_, s)| !s.is_empty())
+        .map(|(index, s)| parse_token(index, s))
+        .collect()
+}
+
+/// Parse exactly `N` non-empty tokens, such as the `dest src len` lines of
+/// day 5.
+///
+/// ```
+/// let [dest, src, len] = aoc_common::tokens::parse_n::<u64, 3>("50 98 2".split(' '));
+/// assert_eq!((dest, src, len), (50, 98, 2));
+/// ```
+pub fn parse_n<'a, T, const N: usize>(iter: impl Iterator<Item = &'a str>) -> [T; N]
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    match try_parse_n(iter) {
+        Ok(values) => values,
+        Err(err) => panic!("{}", err),
+    }
+}
+
+/// Like `parse_n`, but returns an error instead of panicking.
+pub fn try_parse_n<'a, T, const N: usize>(
+    iter: impl Iterator<Item = &'a str>,
+) -> Result<[T; N], TokenError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let values = try_parse_iter(iter)?;
+    let found = values.len();
+    values.try_into().map_err(|_| TokenError::WrongCount {
+        expected: N,
+        found,
+    })
+}
+
+/// Extract every integer from arbitrary text. A `-` directly in front of a
+/// number is a sign, unless it follows a letter or digit, so ranges such as
+/// `3-5` give two positive numbers.
+///
+/// # Panics
+///
+/// Panics if a number does not fit in an `i64`.
+///
+/// ```
+/// assert_eq!(aoc_common::tokens::ints("x=-3, y=7"), vec![-3, 7]);
+/// ```
+pub fn ints(s: &str) -> Vec<i64> {
+    let bytes = s.as_bytes();
+    let mut res = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let mut start = i;
+        let is_sign = start > 0
+            && bytes[start - 1] == b'-'
+            && (start == 1 || !bytes[start - 2].is_ascii_alphanumeric());
+        if is_sign {
+            start -= 1;
+        }
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        res.push(s[start..i].parse().expect("Integer out of range."));
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_parse() {
+        assert_eq!(try_parse_iter::<u32>("1  2 3".split(' ')), Ok(vec![1, 2, 3]));
+        assert_eq!(try_parse_iter::<u32>("1  x 3".split(' ')), Err(TokenError::InvalidToken {
+            index: 2,
+            text: "x".to_string(),
+            message: "invalid digit found in string".to_string(),
+        }));
+        assert_eq!(try_parse_n::<u32, 2>("1 2 3".split(' ')), Err(TokenError::WrongCount {
+            expected: 2,
+            found: 3,
+        }));
+    }
+
+    #[test]
+    fn lazy() {
+        let mut iter = parse_iter_lazy::<u32>("1  x 3".split(' '));
+        assert_eq!(iter.next(), Some(Ok(1)));
+        assert!(matches!(iter.next(), Some(Err(TokenError::InvalidToken { index: 2, .. }))));
+        assert_eq!(iter.next(), Some(Ok(3)));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn extract_ints() {
+        assert_eq!(ints("x=-3, y=7"), vec![-3, 7]);
+        assert_eq!(ints("3-5 -12abc-4 a-1"), vec![3, 5, -12, 4, 1]);
+        assert_eq!(ints("-"), vec![]);
+    }
+}
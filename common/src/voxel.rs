@@ -0,0 +1,284 @@
+//! Points in any number of dimensions, and sets of unit cubes.
+//!
+//! Two cubes of a [`VoxelSet`] are connected if they share a face, so the
+//! surface area counts every face that doesn't touch another cube.
+
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Sub};
+use std::str::FromStr;
+
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash,PartialOrd,Ord)]
+pub struct PointN<const N: usize>(pub [i64; N]);
+
+pub type Point3 = PointN<3>;
+pub type Point4 = PointN<4>;
+
+impl<const N: usize> PointN<N> {
+    pub const ORIGIN: Self = PointN([0; N]);
+
+    pub fn manhattan(self, other: Self) -> u64 {
+        (0..N).map(|i| self.0[i].abs_diff(other.0[i])).sum()
+    }
+    /// The `2 * N` points that differ by one in a single coordinate, such as
+    /// the 6 cubes sharing a face with a cube.
+    pub fn face_neighbors(self) -> impl Iterator<Item = Self> {
+        (0..2 * N).map(move |i| {
+            let mut p = self;
+            p.0[i / 2] += if i % 2 == 0 { -1 } else { 1 };
+            p
+        })
+    }
+    /// The `3^N - 1` points that differ by at most one in every coordinate,
+    /// such as the 26 cubes around a cube or the 80 cells around a point in
+    /// four dimensions.
+    pub fn all_neighbors(self) -> impl Iterator<Item = Self> {
+        let count = 3usize.pow(N as u32);
+        (0..count).filter(move |&i| i != count / 2).map(move |mut i| {
+            let mut p = self;
+            for coord in &mut p.0 {
+                *coord += (i % 3) as i64 - 1;
+                i /= 3;
+            }
+            p
+        })
+    }
+}
+
+impl<const N: usize> Default for PointN<N> {
+    fn default() -> Self {
+        Self::ORIGIN
+    }
+}
+
+impl<const N: usize> Index<usize> for PointN<N> {
+    type Output = i64;
+    fn index(&self, i: usize) -> &i64 {
+        &self.0[i]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for PointN<N> {
+    fn index_mut(&mut self, i: usize) -> &mut i64 {
+        &mut self.0[i]
+    }
+}
+
+impl<const N: usize> Add for PointN<N> {
+    type Output = Self;
+    fn add(mut self, other: Self) -> Self {
+        for i in 0..N {
+            self.0[i] += other.0[i];
+        }
+        self
+    }
+}
+
+impl<const N: usize> Sub for PointN<N> {
+    type Output = Self;
+    fn sub(mut self, other: Self) -> Self {
+        for i in 0..N {
+            self.0[i] -= other.0[i];
+        }
+        self
+    }
+}
+
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct ParsePointNError {
+    pub text: String,
+    pub dimensions: usize,
+}
+
+impl fmt::Display for ParsePointNError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected {} comma separated integers, found {:?}", self.dimensions, self.text)
+    }
+}
+
+impl std::error::Error for ParsePointNError {}
+
+impl<const N: usize> FromStr for PointN<N> {
+    type Err = ParsePointNError;
+
+    /// Parse comma separated coordinates such as `2,-1,5`.
+    fn from_str(s: &str) -> Result<Self, ParsePointNError> {
+        let invalid = || ParsePointNError {
+            text: s.to_string(),
+            dimensions: N,
+        };
+        let mut p = Self::ORIGIN;
+        let mut parts = s.split(',');
+        for coord in &mut p.0 {
+            let part = parts.next().ok_or_else(invalid)?;
+            *coord = part.trim().parse().map_err(|_| invalid())?;
+        }
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(p)
+    }
+}
+
+/// An axis-aligned box. Both corners are inclusive.
+#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]
+pub struct Bounds<const N: usize> {
+    pub min: PointN<N>,
+    pub max: PointN<N>,
+}
+
+impl<const N: usize> Bounds<N> {
+    /// The smallest box containing every point, or `None` if there are no
+    /// points.
+    pub fn of<I: IntoIterator<Item = PointN<N>>>(points: I) -> Option<Self> {
+        let mut iter = points.into_iter();
+        let first = iter.next()?;
+        let mut bounds = Bounds { min: first, max: first };
+        for p in iter {
+            for i in 0..N {
+                bounds.min[i] = i64::min(bounds.min[i], p[i]);
+                bounds.max[i] = i64::max(bounds.max[i], p[i]);
+            }
+        }
+        Some(bounds)
+    }
+    pub fn contains(&self, p: PointN<N>) -> bool {
+        (0..N).all(|i| self.min[i] <= p[i] && p[i] <= self.max[i])
+    }
+    /// Grow the box by `by` in every direction.
+    pub fn expand(&self, by: i64) -> Self {
+        Bounds {
+            min: self.min - PointN([by; N]),
+            max: self.max + PointN([by; N]),
+        }
+    }
+    /// The number of points in the box.
+    pub fn volume(&self) -> u64 {
+        (0..N).map(|i| self.min[i].abs_diff(self.max[i]) + 1).product()
+    }
+}
+
+/// A sparse set of unit cubes, each identified by its corner.
+#[derive(Clone,Debug,Default,Eq,PartialEq)]
+pub struct VoxelSet<const N: usize> {
+    cells: HashSet<PointN<N>>,
+}
+
+impl<const N: usize> VoxelSet<N> {
+    pub fn new() -> Self {
+        VoxelSet {
+            cells: HashSet::new(),
+        }
+    }
+    pub fn insert(&mut self, p: PointN<N>) -> bool {
+        self.cells.insert(p)
+    }
+    pub fn remove(&mut self, p: PointN<N>) -> bool {
+        self.cells.remove(&p)
+    }
+    pub fn contains(&self, p: PointN<N>) -> bool {
+        self.cells.contains(&p)
+    }
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = PointN<N>> + '_ {
+        self.cells.iter().copied()
+    }
+    pub fn bounds(&self) -> Option<Bounds<N>> {
+        Bounds::of(self.iter())
+    }
+
+    /// The number of faces not shared with another cube of the set,
+    /// including faces facing enclosed pockets.
+    pub fn surface_area(&self) -> usize {
+        self.iter()
+            .flat_map(PointN::face_neighbors)
+            .filter(|p| !self.contains(*p))
+            .count()
+    }
+
+    /// The empty cells reachable from outside the set, within a box one cell
+    /// larger than the set on every side.
+    pub fn exterior(&self) -> VoxelSet<N> {
+        let mut exterior = VoxelSet::new();
+        let bounds = match self.bounds() {
+            Some(bounds) => bounds.expand(1),
+            None => return exterior,
+        };
+        let mut queue = VecDeque::from([bounds.min]);
+        exterior.insert(bounds.min);
+        while let Some(p) = queue.pop_front() {
+            for next in p.face_neighbors() {
+                if bounds.contains(next) && !self.contains(next) && exterior.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        exterior
+    }
+
+    /// The number of faces that can be reached from outside the set.
+    pub fn exterior_surface_area(&self) -> usize {
+        let exterior = self.exterior();
+        self.iter()
+            .flat_map(PointN::face_neighbors)
+            .filter(|p| exterior.contains(*p))
+            .count()
+    }
+}
+
+impl<const N: usize> FromIterator<PointN<N>> for VoxelSet<N> {
+    fn from_iter<I: IntoIterator<Item = PointN<N>>>(iter: I) -> Self {
+        VoxelSet {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbors() {
+        let p = Point3::ORIGIN;
+        assert_eq!(p.face_neighbors().count(), 6);
+        assert_eq!(p.all_neighbors().count(), 26);
+        assert!(p.all_neighbors().all(|n| n != p && n.manhattan(p) <= 3));
+        assert_eq!(Point4::ORIGIN.all_neighbors().collect::<HashSet<_>>().len(), 80);
+        assert_eq!(PointN([1, 2]).face_neighbors().collect::<Vec<_>>(),
+            vec![PointN([0, 2]), PointN([2, 2]), PointN([1, 1]), PointN([1, 3])]);
+    }
+
+    #[test]
+    fn parse_and_bounds() {
+        assert_eq!("2,-1,5".parse(), Ok(PointN([2, -1, 5])));
+        assert!("2,-1".parse::<Point3>().is_err());
+        assert!("2,-1,5,0".parse::<Point3>().is_err());
+
+        let bounds = Bounds::of([PointN([1, 5, 0]), PointN([3, 2, 0])]).unwrap();
+        assert_eq!(bounds, Bounds { min: PointN([1, 2, 0]), max: PointN([3, 5, 0]) });
+        assert_eq!(bounds.volume(), 12);
+        assert!(bounds.contains(PointN([2, 2, 0])));
+        assert!(!bounds.contains(PointN([2, 2, 1])));
+        assert_eq!(bounds.expand(1).volume(), 5 * 6 * 3);
+    }
+
+    #[test]
+    fn lava_droplet() {
+        let input = "2,2,2 1,2,2 3,2,2 2,1,2 2,3,2 2,2,1 2,2,3 2,2,4 2,2,6 \
+                     1,2,5 3,2,5 2,1,5 2,3,5";
+        let droplet: VoxelSet<3> = input.split(' ').map(|p| p.parse().unwrap()).collect();
+        assert_eq!(droplet.surface_area(), 64);
+        assert_eq!(droplet.exterior_surface_area(), 58);
+
+        let pair: VoxelSet<3> = [PointN([1, 1, 1]), PointN([2, 1, 1])].into_iter().collect();
+        assert_eq!(pair.surface_area(), 10);
+        assert_eq!(pair.exterior_surface_area(), 10);
+        assert_eq!(VoxelSet::<3>::new().exterior_surface_area(), 0);
+    }
+}